@@ -0,0 +1,65 @@
+use anathema::component::Context;
+use anathema::resolver::ValueKind;
+use anathema::state::State;
+use crate::graph::graph::as_f32;
+
+/// Where a widget reads its attributes from.
+/// This is the component context when running, and a plain list of attributes
+/// when a widget is driven by the test harness, so both go through the same parsing.
+pub trait Attributes {
+    fn text(&self, key: &str) -> Option<&str>;
+    fn number(&self, key: &str) -> Option<f32>;
+    fn integer(&self, key: &str) -> Option<i64>;
+    fn flag(&self, key: &str) -> Option<bool>;
+    /// A list of numbers, either written out or bound to a list in the state
+    fn numbers(&self, key: &str) -> Option<Vec<f32>>;
+    /// A list of strings, either written out or bound to a list in the state
+    fn texts(&self, key: &str) -> Option<Vec<String>>;
+
+    fn character(&self, key: &str) -> Option<char> {
+        self.text(key).and_then(|text| text.chars().next())
+    }
+}
+
+impl<S: State> Attributes for Context<'_, '_, S> {
+    fn text(&self, key: &str) -> Option<&str> {
+        self.attributes.get_as::<&str>(key)
+    }
+
+    fn number(&self, key: &str) -> Option<f32> {
+        self.attributes.get_as::<f32>(key)
+    }
+
+    fn integer(&self, key: &str) -> Option<i64> {
+        self.attributes.get_as::<i64>(key)
+    }
+
+    fn flag(&self, key: &str) -> Option<bool> {
+        self.attributes.get_as::<bool>(key)
+    }
+
+    fn numbers(&self, key: &str) -> Option<Vec<f32>> {
+        match self.attributes.get(key)? {
+            ValueKind::List(values) => Some(values.iter()
+                .filter_map(as_f32)
+                .collect()),
+            ValueKind::DynList(values) => Some(values.as_state().unwrap().as_any_list().unwrap().iter()
+                .filter_map(|value| value.as_state().unwrap().as_float())
+                .map(|value| value as f32)
+                .collect()),
+            _ => None,
+        }
+    }
+
+    fn texts(&self, key: &str) -> Option<Vec<String>> {
+        match self.attributes.get(key)? {
+            ValueKind::List(values) => Some(values.iter()
+                .map(|value| value.as_str().unwrap_or_default().to_string())
+                .collect()),
+            ValueKind::DynList(values) => Some(values.as_state().unwrap().as_any_list().unwrap().iter()
+                .map(|value| value.as_state().unwrap().as_str().unwrap_or_default().to_string())
+                .collect()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use anathema::state::Color;
+
+/// How many colours the terminal can display
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColourMode {
+    #[default]
+    TrueColour,
+    Ansi256,
+}
+
+impl ColourMode {
+    /// Uses `COLORTERM` to decide if truecolor is available
+    pub fn detect() -> Self {
+        match std::env::var("COLORTERM").as_deref() {
+            Ok("truecolor") | Ok("24bit") => ColourMode::TrueColour,
+            _ => ColourMode::Ansi256,
+        }
+    }
+
+    /// Reads the mode from a `colours` attribute, falling back to detection
+    pub fn from_attribute(value: Option<&str>) -> Self {
+        match value {
+            Some("truecolor") => ColourMode::TrueColour,
+            Some("256") => ColourMode::Ansi256,
+            _ => Self::detect(),
+        }
+    }
+}
+
+/// A gradient between evenly spaced colour stops
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColourRamp {
+    stops: Vec<(u8, u8, u8)>,
+}
+
+impl Default for ColourRamp {
+    fn default() -> Self {
+        Self::heat()
+    }
+}
+
+impl From<&str> for ColourRamp {
+    fn from(value: &str) -> Self {
+        match value {
+            "grey" => ColourRamp::grey(),
+            "starlight" => ColourRamp::starlight(),
+            "matrix" => ColourRamp::matrix(),
+            "ocean" => ColourRamp::ocean(),
+            "traffic" => ColourRamp::traffic(),
+            _ => ColourRamp::heat(),
+        }
+    }
+}
+
+impl ColourRamp {
+    pub fn new(stops: Vec<(u8, u8, u8)>) -> Self {
+        assert!(!stops.is_empty(), "a colour ramp needs at least one stop");
+        Self { stops }
+    }
+
+    pub fn heat() -> Self {
+        Self::new(vec![(0, 0, 0), (128, 0, 0), (255, 64, 0), (255, 200, 0), (255, 255, 255)])
+    }
+
+    pub fn grey() -> Self {
+        Self::new(vec![(24, 24, 24), (255, 255, 255)])
+    }
+
+    /// Dim grey through to white, with a hint of blue in between
+    pub fn starlight() -> Self {
+        Self::new(vec![(48, 48, 48), (150, 160, 190), (255, 255, 255)])
+    }
+
+    /// Black through green to the pale head of a falling drop
+    pub fn matrix() -> Self {
+        Self::new(vec![(0, 0, 0), (0, 90, 20), (0, 220, 60), (200, 255, 200)])
+    }
+
+    pub fn ocean() -> Self {
+        Self::new(vec![(0, 0, 48), (0, 64, 160), (0, 200, 220), (220, 255, 255)])
+    }
+
+    pub fn traffic() -> Self {
+        Self::new(vec![(0, 200, 0), (230, 200, 0), (220, 0, 0)])
+    }
+
+    /// The rgb value at `t`, where `t` is clamped to `0.0..=1.0`
+    pub fn sample(&self, t: f32) -> (u8, u8, u8) {
+        if self.stops.len() == 1 {
+            return self.stops[0];
+        }
+
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let position = t * (self.stops.len() - 1) as f32;
+        let index = (position.floor() as usize).min(self.stops.len() - 2);
+        let fraction = position - index as f32;
+
+        let (from, to) = (self.stops[index], self.stops[index + 1]);
+        (lerp(from.0, to.0, fraction), lerp(from.1, to.1, fraction), lerp(from.2, to.2, fraction))
+    }
+
+    /// The colour at `t` for the given colour mode
+    pub fn colour(&self, t: f32, mode: ColourMode) -> Color {
+        let (r, g, b) = self.sample(t);
+        match mode {
+            ColourMode::TrueColour => Color::Rgb(r, g, b),
+            ColourMode::Ansi256 => Color::AnsiVal(to_ansi256(r, g, b)),
+        }
+    }
+}
+
+fn lerp(from: u8, to: u8, fraction: f32) -> u8 {
+    (from as f32 + (to as f32 - from as f32) * fraction).round() as u8
+}
+
+/// Maps an rgb value onto the 6x6x6 colour cube of the 256 colour palette,
+/// using the greyscale ramp when the colour has no hue.
+pub fn to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    if r == g && g == b {
+        return match r {
+            0..8 => 16,
+            248.. => 231,
+            _ => 232 + ((r as u16 - 8) * 24 / 240) as u8,
+        };
+    }
+
+    let cube = |value: u8| ((value as u16 * 5 + 127) / 255) as u8;
+    16 + 36 * cube(r) + 6 * cube(g) + cube(b)
+}
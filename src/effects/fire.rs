@@ -0,0 +1,107 @@
+use std::time::Duration;
+use anathema::component::{Children, Component, Context};
+use anathema::default_widgets::Canvas;
+use anathema::geometry::Size;
+use rand::Rng;
+use crate::attributes::attributes::Attributes;
+use crate::effects::palette::Palette;
+use crate::timing::step::FixedStep;
+use crate::random::seed::SeededRng;
+use crate::surface::surface::Surface;
+
+/// The hottest a pixel gets, the bottom row always burns at this heat
+const MAX_HEAT: u8 = 36;
+
+/// The Doom fire: the bottom row burns at full heat, and every step each pixel
+/// rises one row, drifting sideways and cooling down at random
+pub struct Fire {
+    /// Heat of every pixel, row by row, two rows per line of the canvas
+    pixels: Vec<u8>,
+    width: usize,
+    rows: usize,
+    rng: SeededRng,
+    step: FixedStep,
+    palette: Palette,
+}
+
+impl Default for Fire {
+    fn default() -> Self {
+        Self {
+            pixels: vec![],
+            width: 0,
+            rows: 0,
+            rng: SeededRng::default(),
+            step: FixedStep::new(30.0),
+            palette: Palette::new("heat"),
+        }
+    }
+}
+
+impl Fire {
+    pub(crate) fn configure(&mut self, attributes: &impl Attributes) {
+        self.rng.reseed(attributes.integer("seed").map(|seed| seed as u64));
+        self.step.set_rate(attributes.number("rate").unwrap_or(30.0));
+        self.palette = Palette::from_attributes(attributes, "heat");
+    }
+
+    /// Burns for the time that has passed and draws the fire
+    pub(crate) fn tick(&mut self, canvas: &mut impl Surface, size: Size, dt: Duration) {
+        self.resize(size);
+        for _ in 0..self.step.advance(dt) {
+            self.spread();
+        }
+
+        self.palette.draw_pixels(canvas, size, |x, y| Some(self.heat(x, y)));
+    }
+
+    /// Starts over with a cold fire above the burning bottom row when the canvas changes size
+    fn resize(&mut self, size: Size) {
+        let (width, rows) = (size.width as usize, size.height as usize * 2);
+        if (width, rows) == (self.width, self.rows) {
+            return;
+        }
+
+        self.width = width;
+        self.rows = rows;
+        self.pixels = vec![0; width * rows];
+        if let Some(bottom) = self.pixels.len().checked_sub(width) {
+            self.pixels[bottom..].fill(MAX_HEAT);
+        }
+    }
+
+    fn spread(&mut self) {
+        let rng = self.rng.rng();
+        for x in 0..self.width {
+            for y in 1..self.rows {
+                let heat = self.pixels[y * self.width + x];
+                let drift = rng.random_range(0..3);
+                let above = (x + 1).checked_sub(drift)
+                    .filter(|&x| x < self.width)
+                    .unwrap_or(x);
+
+                let cooling = if heat == 0 { 0 } else { drift as u8 & 1 };
+                self.pixels[(y - 1) * self.width + above] = heat - cooling;
+            }
+        }
+    }
+
+    fn heat(&self, x: u16, y: u16) -> f32 {
+        let heat = self.pixels.get(y as usize * self.width + x as usize).copied().unwrap_or(0);
+        heat as f32 / MAX_HEAT as f32
+    }
+}
+
+impl Component for Fire {
+    type State = ();
+    type Message = ();
+
+    fn on_tick(&mut self, _state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, dt: Duration) {
+        self.configure(&context);
+
+        children.elements().by_tag("canvas")
+            .first(|el, _| {
+                let size = el.size();
+                self.tick(el.to::<Canvas>(), size, dt);
+            });
+    }
+}
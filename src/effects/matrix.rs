@@ -0,0 +1,166 @@
+use std::time::Duration;
+use anathema::component::{Children, Component, Context};
+use anathema::default_widgets::Canvas;
+use anathema::geometry::{LocalPos, Size};
+use anathema::widgets::Style;
+use rand::Rng;
+use crate::attributes::attributes::Attributes;
+use crate::effects::palette::Palette;
+use crate::timing::step::FixedStep;
+use crate::random::seed::SeededRng;
+use crate::surface::surface::Surface;
+
+/// Half width katakana and digits, as close to the film as a terminal gets
+pub(crate) const GLYPHS: &str = "ｦｱｳｴｵｶｷｹｺｻｼｽｾｿﾀﾂﾃﾅﾆﾇﾈﾊﾋﾎﾏﾐﾑﾒﾓﾔﾕﾗﾘﾜ0123456789";
+
+/// A falling streak of glyphs in a single column
+struct Raindrop {
+    /// Row of the bright head of the drop
+    head: f32,
+    /// Rows per second
+    speed: f32,
+    length: f32,
+}
+
+impl Raindrop {
+    /// Starts above the canvas, so drops enter one at a time
+    fn new(rng: &mut impl Rng, height: usize) -> Self {
+        let height = height.max(1) as f32;
+        Self {
+            head: -rng.random_range(0.0..height),
+            speed: rng.random_range(8.0..20.0),
+            length: rng.random_range(height / 4.0..height * 0.75 + 1.0),
+        }
+    }
+
+    /// How bright the row is, 1 at the head fading to 0 at the end of the tail
+    fn brightness(&self, row: usize) -> f32 {
+        let distance = self.head - row as f32;
+        if distance < 0.0 || distance > self.length {
+            0.0
+        } else {
+            1.0 - distance / self.length
+        }
+    }
+}
+
+/// Digital rain, a drop of glyphs falling down every column.
+/// The glyphs stay in place while the drops pass over them, and now and then
+/// one of them changes.
+pub struct Matrix {
+    drops: Vec<Raindrop>,
+    glyphs: Vec<char>,
+    width: usize,
+    height: usize,
+    rng: SeededRng,
+    /// How often a glyph changes
+    flicker: FixedStep,
+    /// Scales how fast the drops fall
+    speed: f32,
+    palette: Palette,
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self {
+            drops: vec![],
+            glyphs: vec![],
+            width: 0,
+            height: 0,
+            rng: SeededRng::default(),
+            flicker: FixedStep::new(20.0),
+            speed: 1.0,
+            palette: Palette::new("matrix"),
+        }
+    }
+}
+
+impl Matrix {
+    pub(crate) fn configure(&mut self, attributes: &impl Attributes) {
+        if self.rng.reseed(attributes.integer("seed").map(|seed| seed as u64)) {
+            // Lay the rain out again from the new seed
+            self.width = 0;
+            self.height = 0;
+        }
+        self.speed = attributes.number("speed").unwrap_or(1.0);
+        self.flicker.set_rate(attributes.number("flicker").unwrap_or(20.0));
+        self.palette = Palette::from_attributes(attributes, "matrix");
+    }
+
+    /// Lets the rain fall for the time that has passed and draws it
+    pub(crate) fn tick(&mut self, canvas: &mut impl Surface, size: Size, dt: Duration) {
+        self.resize(size);
+        self.update(dt);
+        self.draw(canvas);
+    }
+
+    fn resize(&mut self, size: Size) {
+        let (width, height) = (size.width as usize, size.height as usize);
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+
+        self.width = width;
+        self.height = height;
+        let rng = self.rng.rng();
+        let glyphs: Vec<char> = GLYPHS.chars().collect();
+        self.glyphs = (0..width * height)
+            .map(|_| glyphs[rng.random_range(0..glyphs.len())])
+            .collect();
+        self.drops = (0..width)
+            .map(|_| Raindrop::new(rng, height))
+            .collect();
+    }
+
+    fn update(&mut self, dt: Duration) {
+        let rng = self.rng.rng();
+        let height = self.height;
+        for drop in self.drops.iter_mut() {
+            drop.head += drop.speed * self.speed * dt.as_secs_f32();
+            if drop.head - drop.length > height as f32 {
+                *drop = Raindrop::new(rng, height);
+            }
+        }
+
+        if self.glyphs.is_empty() {
+            return;
+        }
+        let glyphs: Vec<char> = GLYPHS.chars().collect();
+        for _ in 0..self.flicker.advance(dt) {
+            let cell = rng.random_range(0..self.glyphs.len());
+            self.glyphs[cell] = glyphs[rng.random_range(0..glyphs.len())];
+        }
+    }
+
+    fn draw(&self, canvas: &mut impl Surface) {
+        for (x, drop) in self.drops.iter().enumerate() {
+            for y in 0..self.height {
+                let brightness = drop.brightness(y);
+                let pos = LocalPos::new(x as u16, y as u16);
+                if brightness <= 0.0 {
+                    canvas.put(' ', Style::new(), pos);
+                    continue;
+                }
+
+                let mut style = Style::new();
+                style.set_fg(self.palette.colour(brightness));
+                canvas.put(self.glyphs[y * self.width + x], style, pos);
+            }
+        }
+    }
+}
+
+impl Component for Matrix {
+    type State = ();
+    type Message = ();
+
+    fn on_tick(&mut self, _state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, dt: Duration) {
+        self.configure(&context);
+
+        children.elements().by_tag("canvas")
+            .first(|el, _| {
+                let size = el.size();
+                self.tick(el.to::<Canvas>(), size, dt);
+            });
+    }
+}
@@ -0,0 +1,4 @@
+pub mod fire;
+pub mod matrix;
+pub mod palette;
+pub mod plasma;
@@ -0,0 +1,58 @@
+use anathema::geometry::{LocalPos, Size};
+use anathema::state::Color;
+use anathema::widgets::Style;
+use crate::attributes::attributes::Attributes;
+use crate::colour::ramp::{ColourMode, ColourRamp};
+use crate::surface::surface::Surface;
+
+/// The colour ramp of a widget and how the terminal displays it, read from
+/// the `ramp` and `colours` attributes
+pub struct Palette {
+    pub ramp: ColourRamp,
+    pub mode: ColourMode,
+}
+
+impl Palette {
+    pub fn new(ramp: &str) -> Self {
+        Self {
+            ramp: ramp.into(),
+            mode: ColourMode::default(),
+        }
+    }
+
+    pub fn from_attributes(attributes: &impl Attributes, default_ramp: &str) -> Self {
+        Self {
+            ramp: attributes.text("ramp")
+                .unwrap_or(default_ramp).into(),
+            mode: ColourMode::from_attribute(attributes.text("colours")),
+        }
+    }
+
+    /// The colour at `t`, where `t` is clamped to `0.0..=1.0`
+    pub fn colour(&self, t: f32) -> Color {
+        self.ramp.colour(t, self.mode)
+    }
+
+    /// Fills the canvas with half blocks, two pixels per cell.
+    /// `pixel` is called with the column and the half row, and returns where on
+    /// the ramp the pixel is, or `None` to leave the pixel empty.
+    pub fn draw_pixels(&self, canvas: &mut impl Surface, size: Size, pixel: impl Fn(u16, u16) -> Option<f32>) {
+        for y in 0..size.height {
+            for x in 0..size.width {
+                let top = pixel(x, y * 2);
+                let bottom = pixel(x, y * 2 + 1);
+
+                let mut style = Style::new();
+                if let Some(t) = top {
+                    style.set_fg(self.colour(t));
+                }
+                if let Some(t) = bottom {
+                    style.set_bg(self.colour(t));
+                }
+
+                let character = if top.is_some() { '▀' } else { ' ' };
+                canvas.put(character, style, LocalPos::new(x, y));
+            }
+        }
+    }
+}
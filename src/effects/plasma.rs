@@ -0,0 +1,53 @@
+use std::time::Duration;
+use anathema::component::{Children, Component, Context};
+use anathema::default_widgets::Canvas;
+use crate::attributes::attributes::Attributes;
+use crate::effects::palette::Palette;
+
+/// The classic plasma, a sum of sine waves over x, y and the distance from the
+/// centre that shift over time
+#[derive(Default)]
+pub struct Plasma {
+    /// Seconds the plasma has been running for, scaled by the speed
+    time: f32,
+}
+
+impl Plasma {
+    /// Where on the ramp the pixel is at the current time
+    fn value(&self, x: f32, y: f32, scale: f32) -> f32 {
+        let (x, y) = (x * scale, y * scale);
+        let time = self.time;
+
+        let sum = (x + time).sin()
+            + (y + time * 0.5).sin()
+            + ((x + y) * 0.5 + time).sin()
+            + ((x * x + y * y).sqrt() - time * 1.5).sin();
+
+        // The sum is between -4 and 4
+        (sum + 4.0) / 8.0
+    }
+}
+
+impl Component for Plasma {
+    type State = ();
+    type Message = ();
+
+    fn on_tick(&mut self, _state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, dt: Duration) {
+        let speed = context.number("speed").unwrap_or(1.0);
+        let scale = context.number("scale").unwrap_or(0.2);
+        let palette = Palette::from_attributes(&context, "ocean");
+        self.time += dt.as_secs_f32() * speed;
+
+        children.elements().by_tag("canvas")
+            .first(|el, _| {
+                let size = el.size();
+                let (center_x, center_y) = (size.width as f32 / 2.0, size.height as f32);
+                let canvas = el.to::<Canvas>();
+                // A pixel is half a cell tall and cells are twice as tall as they are wide,
+                // so pixels are already about square and the rings come out round
+                palette.draw_pixels(canvas, size, |x, y| {
+                    Some(self.value(x as f32 - center_x, y as f32 - center_y, scale))
+                });
+            });
+    }
+}
@@ -0,0 +1,150 @@
+use std::f32::consts::PI;
+use std::time::Duration;
+use anathema::component::{Children, Component, Context};
+use anathema::default_widgets::Canvas;
+use anathema::geometry::{LocalPos, Size};
+use anathema::state::{Color, State, Value};
+use anathema::widgets::Style;
+use crate::attributes::attributes::Attributes;
+
+/// Partially filled cells, from one eighth to seven eighths
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+#[derive(Default)]
+pub struct Gauge {
+    value: f32,
+    max: f32,
+    /// Percentages where the gauge turns yellow and red
+    warn: f32,
+    critical: f32,
+    orientation: Orientation,
+}
+
+#[derive(State, Default)]
+pub struct GaugeState {
+    pub percent: Value<u16>,
+}
+
+#[derive(Clone, Copy, Default)]
+enum Orientation {
+    #[default]
+    Horizontal, Radial
+}
+
+impl From<&str> for Orientation {
+    fn from(value: &str) -> Self {
+        match value {
+            "radial" => Orientation::Radial,
+            _ => Orientation::Horizontal,
+        }
+    }
+}
+
+impl Gauge {
+    pub(crate) fn configure(&mut self, attributes: &impl Attributes) {
+        self.value = attributes.number("value").unwrap_or(0.0);
+        self.max = attributes.number("max").unwrap_or(100.0);
+        self.warn = attributes.number("warn").unwrap_or(70.0);
+        self.critical = attributes.number("critical").unwrap_or(90.0);
+        self.orientation = attributes.text("orientation")
+            .unwrap_or("horizontal").into();
+    }
+
+    /// How full the gauge is, from 0 to 1
+    pub(crate) fn fraction(&self) -> f32 {
+        if self.max > 0.0 { (self.value / self.max).clamp(0.0, 1.0) } else { 0.0 }
+    }
+
+    fn clear_canvas(&self, canvas: &mut Canvas, size: Size) {
+        for y in 0..size.height {
+            for x in 0..size.width {
+                canvas.put(' ', Style::reset(), LocalPos::new(x, y));
+            }
+        }
+    }
+
+    fn draw_horizontal(&self, canvas: &mut Canvas, size: Size, fraction: f32, style: Style) {
+        let eighths = (fraction * size.width as f32 * 8.0).round() as u32;
+        let full = (eighths / 8) as u16;
+        let remainder = (eighths % 8) as usize;
+
+        for y in 0..size.height {
+            for x in 0..full.min(size.width) {
+                canvas.put('█', style, LocalPos::new(x, y));
+            }
+            if remainder > 0 && full < size.width {
+                canvas.put(EIGHTHS[remainder - 1], style, LocalPos::new(full, y));
+            }
+        }
+    }
+
+    /// Draws a half ring around the bottom centre of the canvas, filled
+    /// clockwise from the left.
+    /// Terminal cells are roughly twice as tall as they are wide so the x
+    /// distance is halved to keep the ring round.
+    fn draw_radial(&self, canvas: &mut Canvas, size: Size, fraction: f32, style: Style) {
+        let centre_x = size.width as f32 / 2.0;
+        let centre_y = size.height as f32;
+        let outer = (size.width as f32 / 4.0).min(size.height as f32);
+        let inner = outer * 0.6;
+
+        let mut empty = Style::new();
+        empty.set_fg(Color::DarkGrey);
+
+        for y in 0..size.height {
+            for x in 0..size.width {
+                let dx = (x as f32 + 0.5 - centre_x) / 2.0;
+                let dy = centre_y - (y as f32 + 0.5);
+                let distance = (dx * dx + dy * dy).sqrt();
+                if distance < inner || distance > outer {
+                    continue;
+                }
+
+                // 0.0 on the left, 1.0 on the right
+                let angle = 1.0 - dy.atan2(dx) / PI;
+                if angle <= fraction {
+                    canvas.put('█', style, LocalPos::new(x, y));
+                } else {
+                    canvas.put('░', empty, LocalPos::new(x, y));
+                }
+            }
+        }
+    }
+
+    fn threshold_colour(percent: f32, warn: f32, critical: f32) -> Color {
+        if percent >= critical {
+            Color::Red
+        } else if percent >= warn {
+            Color::Yellow
+        } else {
+            Color::Green
+        }
+    }
+}
+
+impl Component for Gauge {
+    type State = GaugeState;
+    type Message = ();
+
+    fn on_tick(&mut self, state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, _dt: Duration) {
+        self.configure(&context);
+
+        let fraction = self.fraction();
+        let percent = fraction * 100.0;
+        state.percent.set(percent.round() as u16);
+
+        let mut style = Style::new();
+        style.set_fg(Self::threshold_colour(percent, self.warn, self.critical));
+
+        children.elements().by_tag("canvas")
+            .first(|el, _| {
+                let size = el.size();
+                let canvas = el.to::<Canvas>();
+                self.clear_canvas(canvas, size);
+                match self.orientation {
+                    Orientation::Horizontal => self.draw_horizontal(canvas, size, fraction, style),
+                    Orientation::Radial => self.draw_radial(canvas, size, fraction, style),
+                }
+            });
+    }
+}
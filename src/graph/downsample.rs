@@ -0,0 +1,116 @@
+use crate::graph::graph::Candle;
+
+/// How a series is reduced when it has more points than there are columns
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Aggregate {
+    Min,
+    Max,
+    #[default]
+    Mean,
+    Last,
+    /// Largest-Triangle-Three-Buckets, keeps the points that contribute the
+    /// most to the shape of the series
+    Lttb,
+}
+
+impl From<&str> for Aggregate {
+    fn from(value: &str) -> Self {
+        match value {
+            "min" => Aggregate::Min,
+            "max" => Aggregate::Max,
+            "last" => Aggregate::Last,
+            "lttb" => Aggregate::Lttb,
+            _ => Aggregate::Mean,
+        }
+    }
+}
+
+impl Aggregate {
+    /// Reduces the points to `buckets` points
+    pub fn reduce(&self, points: &[f32], buckets: usize) -> Vec<f32> {
+        if buckets == 0 || points.len() <= buckets {
+            return points.to_vec();
+        }
+
+        match self {
+            Aggregate::Min => reduce_buckets(points, buckets, |bucket| bucket.iter().copied().fold(f32::MAX, f32::min)),
+            Aggregate::Max => reduce_buckets(points, buckets, |bucket| bucket.iter().copied().fold(f32::MIN, f32::max)),
+            Aggregate::Mean => reduce_buckets(points, buckets, |bucket| bucket.iter().sum::<f32>() / bucket.len() as f32),
+            Aggregate::Last => reduce_buckets(points, buckets, |bucket| bucket[bucket.len() - 1]),
+            Aggregate::Lttb => lttb(points, buckets),
+        }
+    }
+}
+
+/// Merges neighbouring candles into `buckets` candles, each opening with the
+/// first candle and closing with the last one of its bucket
+pub fn reduce_candles(candles: &[Candle], buckets: usize) -> Vec<Candle> {
+    if buckets == 0 || candles.len() <= buckets {
+        return candles.to_vec();
+    }
+
+    (0..buckets)
+        .map(|bucket| {
+            let start = bucket * candles.len() / buckets;
+            let end = (bucket + 1) * candles.len() / buckets;
+            let bucket = &candles[start..end];
+            Candle {
+                open: bucket[0].open,
+                high: bucket.iter().map(|candle| candle.high).fold(f32::MIN, f32::max),
+                low: bucket.iter().map(|candle| candle.low).fold(f32::MAX, f32::min),
+                close: bucket[bucket.len() - 1].close,
+            }
+        })
+        .collect()
+}
+
+/// Splits the points into evenly sized buckets and reduces each of them to a single point
+fn reduce_buckets(points: &[f32], buckets: usize, reduce: impl Fn(&[f32]) -> f32) -> Vec<f32> {
+    (0..buckets)
+        .map(|bucket| {
+            let start = bucket * points.len() / buckets;
+            let end = (bucket + 1) * points.len() / buckets;
+            reduce(&points[start..end])
+        })
+        .collect()
+}
+
+fn lttb(points: &[f32], threshold: usize) -> Vec<f32> {
+    // The first and last points are always kept
+    if threshold < 3 {
+        return reduce_buckets(points, threshold, |bucket| bucket[bucket.len() - 1]);
+    }
+
+    let bucket_size = (points.len() - 2) as f32 / (threshold - 2) as f32;
+    let mut sampled = Vec::with_capacity(threshold);
+    sampled.push(points[0]);
+    let mut selected = 0;
+
+    for bucket in 0..threshold - 2 {
+        let start = (bucket as f32 * bucket_size) as usize + 1;
+        let end = ((bucket + 1) as f32 * bucket_size) as usize + 1;
+
+        // The average of the next bucket is the third corner of the triangle
+        let next_start = end;
+        let next_end = (((bucket + 2) as f32 * bucket_size) as usize + 1).min(points.len());
+        let next = &points[next_start..next_end.max(next_start + 1).min(points.len())];
+        let average_x = (next_start + next_end - 1) as f32 / 2.0;
+        let average_y = next.iter().sum::<f32>() / next.len() as f32;
+
+        let (selected_x, selected_y) = (selected as f32, points[selected]);
+        let mut largest_area = -1.0;
+        for (index, point) in points.iter().enumerate().take(end).skip(start) {
+            let area = ((selected_x - average_x) * (point - selected_y)
+                - (selected_x - index as f32) * (average_y - selected_y)).abs();
+            if area > largest_area {
+                largest_area = area;
+                selected = index;
+            }
+        }
+
+        sampled.push(points[selected]);
+    }
+
+    sampled.push(points[points.len() - 1]);
+    sampled
+}
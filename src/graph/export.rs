@@ -0,0 +1,178 @@
+use std::fmt::Write;
+use crate::graph::graph::{Graph, GraphType};
+use crate::graph::scale::format_tick;
+use crate::surface::text_grid::TextGrid;
+
+const SVG_WIDTH: f32 = 800.0;
+const SVG_HEIGHT: f32 = 400.0;
+const SVG_MARGIN: f32 = 40.0;
+/// Same order as the bar colours on the canvas
+const SVG_COLOURS: [&str; 4] = ["#3b82f6", "#ef4444", "#22c55e", "#eab308"];
+/// The scales project onto cells, projecting onto this many cells keeps the
+/// precision needed for pixels
+const PRECISION: u16 = 10_000;
+
+/// Writes `<path>.txt`, `<path>.csv` and `<path>.svg`
+pub fn export(graph: &Graph, path: &str) -> std::io::Result<()> {
+    std::fs::write(format!("{path}.txt"), to_text(graph))?;
+    std::fs::write(format!("{path}.csv"), to_csv(graph))?;
+    std::fs::write(format!("{path}.svg"), to_svg(graph))?;
+    Ok(())
+}
+
+/// The graph as it was last drawn, with the y axis labels in front of every row
+pub fn to_text(graph: &Graph) -> String {
+    let Some(size) = graph.canvas_size else {
+        return String::new();
+    };
+
+    let mut grid = TextGrid::new(size);
+    graph.render(&mut grid, size);
+
+    graph.scale.labels(graph.range, size.height).iter()
+        .zip(grid.lines())
+        .map(|(label, line)| format!("{label} {line}").trim_end().to_string() + "\n")
+        .collect()
+}
+
+/// Every point of every series that was received, one row per point.
+/// Box plots are written in the order they are read, `min,p50,p99,max`.
+pub fn to_csv(graph: &Graph) -> String {
+    let box_plot = matches!(graph.graph_type, GraphType::BoxPlot);
+    let mut csv = String::from(match box_plot {
+        true => "series,index,x,y,min,p50,p99,max\n",
+        false => "series,index,x,y,open,high,low,close\n",
+    });
+    let Some(graph_data) = graph.source_data() else {
+        return csv;
+    };
+
+    for (series_index, series) in graph_data.series.iter().enumerate() {
+        for (index, (x, y)) in series.xy().enumerate() {
+            let _ = writeln!(csv, "{series_index},{index},{x},{y},,,,");
+        }
+        for (index, candle) in series.candles.iter().enumerate() {
+            let values = match box_plot {
+                true => [candle.low, candle.open, candle.close, candle.high],
+                false => [candle.open, candle.high, candle.low, candle.close],
+            };
+            let _ = writeln!(
+                csv, "{series_index},{index},{index},,{},{},{},{}",
+                values[0], values[1], values[2], values[3],
+            );
+        }
+    }
+    csv
+}
+
+/// A standalone svg drawn from the data that was received rather than the
+/// canvas, so it is not limited to the resolution of the terminal
+pub fn to_svg(graph: &Graph) -> String {
+    let source = graph.source_data();
+    let (range, x_range) = source.as_ref()
+        .map(|graph_data| graph.domains(graph_data))
+        .unwrap_or((graph.range, graph.x_range));
+    let plot_width = SVG_WIDTH - SVG_MARGIN * 2.0;
+    let plot_height = SVG_HEIGHT - SVG_MARGIN * 2.0;
+    let bottom = SVG_HEIGHT - SVG_MARGIN;
+
+    let to_y = |value: f32| {
+        graph.scale.project(value, range, PRECISION)
+            .map(|cells| bottom - plot_height * cells as f32 / PRECISION as f32)
+    };
+
+    let mut svg = String::new();
+    let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{SVG_WIDTH}" height="{SVG_HEIGHT}" viewBox="0 0 {SVG_WIDTH} {SVG_HEIGHT}">"#);
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+    let _ = writeln!(svg, r#"<g stroke="black" stroke-width="1"><line x1="{SVG_MARGIN}" y1="{SVG_MARGIN}" x2="{SVG_MARGIN}" y2="{bottom}"/><line x1="{SVG_MARGIN}" y1="{bottom}" x2="{}" y2="{bottom}"/></g>"#, SVG_WIDTH - SVG_MARGIN);
+
+    let _ = writeln!(svg, r#"<g font-family="monospace" font-size="10" text-anchor="end">"#);
+    for tick in graph.scale.ticks(range, 5) {
+        if let Some(y) = to_y(tick) {
+            let _ = writeln!(svg, r#"<text x="{}" y="{}">{}</text>"#, SVG_MARGIN - 4.0, y + 3.0, format_tick(tick));
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    let Some(graph_data) = &source else {
+        svg.push_str("</svg>\n");
+        return svg;
+    };
+
+    let len = graph_data.series.iter()
+        .map(|series| series.points.len().max(series.candles.len()))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let slot = plot_width / len as f32;
+    let to_x = |index: usize| SVG_MARGIN + slot * (index as f32 + 0.5);
+    let mut baseline = vec![0.0; len];
+
+    for (series_index, series) in graph_data.series.iter().enumerate() {
+        let colour = SVG_COLOURS[series_index % SVG_COLOURS.len()];
+
+        match graph.graph_type {
+            GraphType::Point => {
+                for (index, point) in series.points.iter().enumerate() {
+                    if let Some(y) = to_y(*point) {
+                        let _ = writeln!(svg, r#"<circle cx="{}" cy="{y}" r="3" fill="{colour}"/>"#, to_x(index));
+                    }
+                }
+            }
+            GraphType::Bar => {
+                let width = slot * 0.8 / graph_data.series.len() as f32;
+                for (index, point) in series.points.iter().enumerate() {
+                    if let (Some(y), Some(base)) = (to_y(*point), to_y(range.0.max(0.0))) {
+                        let x = SVG_MARGIN + slot * (index as f32 + 0.1) + width * series_index as f32;
+                        let _ = writeln!(svg, r#"<rect x="{x}" y="{}" width="{width}" height="{}" fill="{colour}"/>"#, y.min(base), (base - y).abs());
+                    }
+                }
+            }
+            GraphType::Area => {
+                let mut top = vec![];
+                let mut floor = vec![];
+                for (index, point) in series.points.iter().enumerate() {
+                    let base = if graph.stacked { baseline[index] } else { 0.0 };
+                    if let (Some(y), Some(base_y)) = (to_y(base + point), to_y(base)) {
+                        top.push(format!("{},{y}", to_x(index)));
+                        floor.push(format!("{},{base_y}", to_x(index)));
+                    }
+                }
+                floor.reverse();
+                let _ = writeln!(svg, r#"<polygon points="{} {}" fill="{colour}" fill-opacity="0.4" stroke="{colour}"/>"#, top.join(" "), floor.join(" "));
+
+                if graph.stacked {
+                    series.points.iter().enumerate().for_each(|(index, point)| baseline[index] += point);
+                }
+            }
+            GraphType::Scatter => {
+                for (x, y) in series.xy() {
+                    if let (Some(x), Some(y)) = (graph.x_scale.project(x, x_range, PRECISION), to_y(y)) {
+                        let x = SVG_MARGIN + plot_width * x as f32 / PRECISION as f32;
+                        let _ = writeln!(svg, r#"<circle cx="{x}" cy="{y}" r="3" fill="{colour}"/>"#);
+                    }
+                }
+            }
+            GraphType::Candlestick | GraphType::BoxPlot => {
+                for (index, candle) in series.candles.iter().enumerate() {
+                    let colour = match graph.graph_type {
+                        GraphType::Candlestick if candle.close >= candle.open => SVG_COLOURS[2],
+                        GraphType::Candlestick => SVG_COLOURS[1],
+                        _ => colour,
+                    };
+                    let x = to_x(index);
+                    if let (Some(high), Some(low)) = (to_y(candle.high), to_y(candle.low)) {
+                        let _ = writeln!(svg, r#"<line x1="{x}" y1="{high}" x2="{x}" y2="{low}" stroke="{colour}"/>"#);
+                    }
+                    if let (Some(open), Some(close)) = (to_y(candle.open), to_y(candle.close)) {
+                        let width = slot * 0.6;
+                        let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{width}" height="{}" fill="{colour}"/>"#, x - width / 2.0, open.min(close), (open - close).abs().max(1.0));
+                    }
+                }
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -1,11 +1,21 @@
 use std::time::Duration;
-use anathema::component::{Children, Component, Context,State, Value};
+use anathema::component::{Children, Component, Context, KeyCode, KeyEvent, MouseEvent, State, Value};
+use anathema::state::List;
 use anathema::default_widgets::Canvas;
-use anathema::geometry::{LocalPos, Size};
+use anathema::geometry::{LocalPos, Pos, Size};
 use anathema::state::Color;
 use anathema::widgets::{Element, Style};
-use std::io::Write;
 use anathema::resolver::ValueKind;
+use crate::graph::downsample::{reduce_candles, Aggregate};
+use crate::graph::export;
+use crate::graph::scale::{format_tick, Scale};
+use crate::graph::transition::Transition;
+use crate::attributes::attributes::Attributes;
+use crate::graph::stream::{GraphMode, History, HISTORY_LENGTH, SAMPLE_INTERVAL};
+use crate::surface::surface::Surface;
+
+/// Zoom levels are powers of two, positive levels zoom in
+const ZOOM_LEVELS: std::ops::RangeInclusive<i8> = -6..=4;
 
 #[derive(State, Default)]
 pub struct GraphDataState {
@@ -13,6 +23,11 @@ pub struct GraphDataState {
     pub max_height: Value<u16>,
     pub min_height: Value<u16>,
     pub updated: Value<bool>,
+    pub y_labels: Value<List<String>>,
+    pub tooltip: Value<String>,
+    pub view_range: Value<String>,
+    pub export_status: Value<String>,
+    pub x_labels: Value<String>,
 }
 
 #[derive(State, Default)]
@@ -23,69 +38,504 @@ pub struct Range {
 
 #[derive(Default)]
 pub struct Graph {
+    /// What is drawn, the streaming window, downsampled and part way through a transition
     pub graph_data: Option<GraphData>,
+    /// The data as it was last received when replacing
+    pub received: Option<GraphData>,
     pub range: (f32, f32),
+    pub scale: Scale,
+    pub x_range: (f32, f32),
+    pub x_scale: Scale,
+    pub stacked: bool,
+    pub mode: GraphMode,
+    pub history: History,
+    /// Number of points that fit on the canvas when streaming
+    pub window: Option<usize>,
+    pub point_width: u16,
+    /// Index of the point under the cursor
+    pub cursor: Option<usize>,
+    pub zoom_level: i8,
+    pub aggregate: Aggregate,
+    /// Number of points between the most recent point and the right edge when streaming
+    pub pan: usize,
+    pub x_axis: Option<char>,
+    pub y_axis: Option<char>,
+    pub markers: Vec<char>,
+    pub export_path: String,
+    /// Where the bars start and end, labelled under the canvas
+    pub x_edges: Vec<f32>,
+    /// Eases the points to new values when replacing the data
+    pub transition: Transition,
+    /// Size of the canvas when the graph was last drawn
+    pub canvas_size: Option<Size>,
+    pub(crate) graph_type: GraphType,
+    current_layout: Option<Layout>,
 }
 
-#[derive(Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct GraphData {
     pub series: Vec<GraphSeries>,
 }
 
-#[derive(Default)]
+impl GraphData {
+    /// All y values across every series
+    pub fn values(&self) -> impl Iterator<Item = f32> + '_ {
+        self.series.iter().flat_map(|series| {
+            series.points.iter().copied()
+                .chain(series.pairs.iter().map(|(_, y)| *y))
+                .chain(series.candles.iter().flat_map(|candle| [candle.low, candle.high]))
+        })
+    }
+
+    /// The top of every series when the series are stacked on top of each other
+    pub fn stacked_values(&self) -> Vec<f32> {
+        let mut baseline: Vec<f32> = vec![];
+        let mut tops = vec![];
+        for series in self.series.iter() {
+            for (index, point) in series.points.iter().enumerate() {
+                if baseline.len() <= index {
+                    baseline.push(0.0);
+                }
+                baseline[index] += point;
+                tops.push(baseline[index]);
+            }
+        }
+        tops
+    }
+
+    /// All x values across every series, plain points use their index
+    pub fn x_values(&self) -> impl Iterator<Item = f32> + '_ {
+        self.series.iter().flat_map(|series| series.xy().map(|(x, _)| x))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct GraphSeries {
     pub points: Vec<f32>,
+    /// XY pairs for series that are not evenly spaced along x
+    pub pairs: Vec<(f32, f32)>,
+    pub candles: Vec<Candle>,
 }
 
-impl Graph {
-    fn draw_graph(&mut self, children: &mut Children, context: Context<GraphDataState>) {
-        let x_axis = context.attributes.get_as::<char>("x_axis");
-        let y_axis = context.attributes.get_as::<char>("y_axis");
-        let markers = context.attributes.get_as::<&str>("markers")
-            .unwrap_or("@").chars().collect::<Vec<char>>();
-        let graph_type: GraphType = context.attributes.get_as::<&str>("type")
-            .unwrap_or("point").into();
+/// Open, high, low and close for a single x.
+/// Box plots read the same four values as min, p50, p99 and max.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Candle {
+    pub open: f32,
+    pub high: f32,
+    pub low: f32,
+    pub close: f32,
+}
+
+impl Candle {
+    fn from_box(min: f32, p50: f32, p99: f32, max: f32) -> Self {
+        Self { open: p50, high: max, low: min, close: p99 }
+    }
+}
+
+impl GraphSeries {
+    /// The series as xy pairs, plain points are spaced by their index
+    pub fn xy(&self) -> Box<dyn Iterator<Item = (f32, f32)> + '_> {
+        if self.pairs.is_empty() {
+            Box::new(self.points.iter().enumerate().map(|(index, point)| (index as f32, *point)))
+        } else {
+            Box::new(self.pairs.iter().copied())
+        }
+    }
+}
 
+impl Graph {
+    fn draw_graph(&mut self, children: &mut Children) {
         children.elements().by_tag("canvas")
             .first(|el, _| {
                 let size = el.size();
-                let canvas = el.to::<Canvas>();
-                self.draw_axis(canvas, x_axis, y_axis, size);
-                self.draw_data_points(canvas, &markers, &graph_type, size);
+                self.render(el.to::<Canvas>(), size);
             });
     }
 
+    /// Draws the axes, cursor and data onto any surface
+    pub(crate) fn render(&self, surface: &mut impl Surface, size: Size) {
+        self.draw_axis(surface, self.x_axis, self.y_axis, size);
+        self.draw_cursor(surface, size);
+        self.draw_data_points(surface, &self.markers, &self.graph_type, size);
+    }
+
     fn calculate_state(&mut self, state: &mut GraphDataState, el: &mut Element) {
         let size = el.size();
 
-        let mut max_width = 0;
         match &self.graph_data {
             None => {}
             Some(graph_data) => {
-                for series in graph_data.series.iter() {
-                    let point_width = calculate_point_width(series, size);
-                    if point_width < max_width {
-                        max_width = point_width;
-                    }
-                }
-                state.point_width = Value::new(max_width);
+                let point_width = match self.mode {
+                    GraphMode::Stream => self.point_width,
+                    GraphMode::Replace => graph_data.series.iter()
+                        .map(|series| calculate_point_width(series, size))
+                        .min()
+                        .unwrap_or(1),
+                };
+                state.point_width.set(point_width);
 
                 let largest_range_in_series = determine_largest_range_in_series(graph_data);
                 state.max_height.set(largest_range_in_series.1 as u16);
                 state.min_height.set(largest_range_in_series.0 as u16);
             }
         }
+
+        state.y_labels.set(List::from_iter(self.scale.labels(self.range, size.height)));
+        state.x_labels.set(self.x_labels(size));
+    }
+
+    /// Labels the `x_edges` under the bars, every bar starts at its lower edge
+    /// and the last edge ends where the last bar does.
+    /// Labels that would run into the one before are left out.
+    pub(crate) fn x_labels(&self, canvas_size: Size) -> String {
+        let Some(layout) = self.layout(canvas_size).filter(|_| self.x_edges.len() > 1) else {
+            return String::new();
+        };
+
+        let mut line = vec![' '; canvas_size.width as usize];
+        // The first column after the last label and a space
+        let mut free = 0;
+        let mut place = |value: f32, column: usize| {
+            let label = format_tick(value);
+            let end = column + label.chars().count();
+            if column < free || end > line.len() {
+                return;
+            }
+            for (offset, character) in label.chars().enumerate() {
+                line[column + offset] = character;
+            }
+            free = end + 1;
+        };
+
+        // Downsampled bars cover more than one bin
+        let last_edge = self.x_edges.len() - 1;
+        for index in 0..layout.len {
+            place(self.x_edges[index * last_edge / layout.len], layout.column(index, &self.graph_type) as usize);
+        }
+        let end = (layout.column(layout.len - 1, &self.graph_type) + layout.point_width) as usize;
+        let label_width = format_tick(self.x_edges[last_edge]).chars().count();
+        place(self.x_edges[last_edge], end.saturating_sub(label_width));
+
+        line.into_iter().collect::<String>().trim_end().to_string()
+    }
+
+    /// Reads the attributes and receives the data, once every tick
+    pub(crate) fn update(&mut self, attributes: &impl Attributes, data: Option<&ValueKind>, dt: Duration) {
+        self.configure(attributes);
+        self.transition.advance(dt);
+
+        let box_plot = matches!(self.graph_type, GraphType::BoxPlot);
+        if let Some(graph_data) = data.and_then(|data| parse_data(data, box_plot)) {
+            self.receive(graph_data, dt);
+        }
+    }
+
+    fn configure(&mut self, attributes: &impl Attributes) {
+        self.scale = attributes.text("scale")
+            .unwrap_or("linear").into();
+        self.x_scale = attributes.text("x_scale")
+            .unwrap_or("linear").into();
+        self.graph_type = attributes.text("type")
+            .unwrap_or("point").into();
+        self.stacked = attributes.flag("stacked").unwrap_or(false);
+        self.mode = attributes.text("mode")
+            .unwrap_or("replace").into();
+        self.point_width = attributes.integer("point_width").unwrap_or(1) as u16;
+        self.history.set_sample_interval(attributes.integer("sample_interval")
+            .map(|millis| Duration::from_millis(millis as u64))
+            .unwrap_or(SAMPLE_INTERVAL));
+        self.aggregate = attributes.text("aggregate")
+            .unwrap_or("mean").into();
+        self.x_axis = attributes.character("x_axis");
+        self.y_axis = attributes.character("y_axis");
+        self.markers = attributes.text("markers")
+            .unwrap_or("@").chars().collect();
+        self.x_edges = attributes.numbers("x_edges").unwrap_or_default();
+        self.export_path = attributes.text("export_path")
+            .unwrap_or("graph").to_string();
+        self.transition.duration = Duration::from_millis(attributes.integer("transition").unwrap_or(0) as u64);
+        self.transition.easing = attributes.text("easing")
+            .unwrap_or("ease_out").into();
+    }
+
+    /// `dt` is the time since the last tick, streaming graphs sample the data at their own rate
+    pub(crate) fn receive(&mut self, graph_data: GraphData, dt: Duration) {
+        match self.mode {
+            GraphMode::Stream => self.history.push(&graph_data, dt),
+            GraphMode::Replace => {
+                if self.transition.is_enabled() {
+                    self.transition.retarget(&graph_data);
+                }
+                self.received = Some(graph_data.clone());
+                self.graph_data = Some(graph_data);
+            }
+        }
+    }
+
+    /// Works out what to draw for the given canvas size
+    pub(crate) fn prepare(&mut self, size: Size) {
+        self.canvas_size = Some(size);
+        self.update_window(size);
+        self.apply_transition();
+        self.downsample(size);
+        self.update_range();
+        self.current_layout = self.layout(size);
+        self.cursor = match self.current_layout {
+            Some(layout) => self.cursor.map(|cursor| cursor.min(layout.len - 1)),
+            None => None,
+        };
+    }
+
+    /// When streaming, the graph data is the most recent part of the history
+    /// that fits on the canvas, so new points appear on the right and scroll left.
+    fn update_window(&mut self, size: Size) {
+        match self.mode {
+            GraphMode::Replace => self.window = None,
+            GraphMode::Stream => {
+                let columns = size.width.saturating_sub(self.graph_type.first_column());
+                let window = (columns / (self.point_width + 1)).max(1) as usize;
+                let window = match self.zoom_level {
+                    level if level >= 0 => window >> level,
+                    level => window << -level,
+                }.clamp(2, HISTORY_LENGTH);
+
+                self.pan = self.pan.min(self.history.len().saturating_sub(window));
+                self.window = Some(window);
+                self.graph_data = Some(self.history.window(window, self.pan));
+            }
+        }
+    }
+
+    /// Replaces the data that arrived with where the points are on their way to it.
+    /// Streaming graphs scroll instead, so they are drawn as they are.
+    fn apply_transition(&mut self) {
+        if self.mode == GraphMode::Stream || !self.transition.is_enabled() {
+            return;
+        }
+
+        if let Some(graph_data) = &mut self.graph_data {
+            self.transition.apply(graph_data);
+        }
+    }
+
+    /// Where the points of graphs that are indexed by point end up on the canvas
+    fn layout(&self, canvas_size: Size) -> Option<Layout> {
+        let largest_points_len = self.graph_data.as_ref()?.series.iter()
+            .map(|series| series.points.len().max(series.candles.len()))
+            .max()
+            .unwrap_or(0);
+
+        if largest_points_len == 0 {
+            return None;
+        }
+
+        // A streaming graph reserves room for a full window and keeps the
+        // points to the right until the window has filled up
+        let slots = self.window.unwrap_or(largest_points_len).max(largest_points_len);
+        let mut bar_width = (canvas_size.width as usize / slots) as u16;
+        if bar_width > 1 {
+            bar_width -= 1; // Ensure at least one character width for the bar
+        }
+
+        Some(Layout {
+            point_width: bar_width,
+            x_offset: (slots - largest_points_len) as u16 * (bar_width + 1),
+            len: largest_points_len,
+        })
+    }
+
+    fn move_cursor(&mut self, code: KeyCode) -> bool {
+        let Some(last) = self.current_layout.map(|layout| layout.len - 1) else {
+            return false;
+        };
+
+        self.cursor = match code {
+            KeyCode::Left => Some(self.cursor.map_or(last, |cursor| cursor.saturating_sub(1))),
+            KeyCode::Right => Some(self.cursor.map_or(0, |cursor| (cursor + 1).min(last))),
+            KeyCode::Home => Some(0),
+            KeyCode::End => Some(last),
+            KeyCode::Esc => None,
+            _ => return false,
+        };
+        true
+    }
+
+    /// Zooms and pans through the history of a streaming graph
+    fn move_view(&mut self, code: KeyCode) {
+        if self.mode != GraphMode::Stream {
+            return;
+        }
+
+        let window = self.window.unwrap_or(1);
+        match code {
+            KeyCode::Char('+') | KeyCode::Char('=') => self.zoom_level = (self.zoom_level + 1).min(*ZOOM_LEVELS.end()),
+            KeyCode::Char('-') => self.zoom_level = (self.zoom_level - 1).max(*ZOOM_LEVELS.start()),
+            KeyCode::Char('[') => self.pan += (window / 4).max(1),
+            KeyCode::Char(']') => self.pan = self.pan.saturating_sub((window / 4).max(1)),
+            KeyCode::Char('0') => {
+                self.zoom_level = 0;
+                self.pan = 0;
+            }
+            _ => {}
+        }
+    }
+
+    /// Describes which part of the history is visible
+    fn view_range(&self) -> String {
+        let Some(window) = self.window else {
+            return String::new();
+        };
+
+        let len = self.history.len();
+        let end = len.saturating_sub(self.pan);
+        let start = end.saturating_sub(window);
+        let live = if self.pan == 0 { " live" } else { "" };
+        format!("{start}..{end} of {len}{live}")
+    }
+
+    /// The value of every series at the cursor
+    fn tooltip(&self) -> String {
+        let (Some(cursor), Some(graph_data)) = (self.cursor, &self.graph_data) else {
+            return String::new();
+        };
+
+        let values = graph_data.series.iter().enumerate()
+            .filter_map(|(index, series)| {
+                let value = match series.candles.get(cursor) {
+                    Some(candle) => format!(
+                        "{} {} {} {}",
+                        format_tick(candle.open), format_tick(candle.high), format_tick(candle.low), format_tick(candle.close),
+                    ),
+                    None => format_tick(*series.points.get(cursor)?),
+                };
+                Some(format!("{}: {value}", index + 1))
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        format!("[{cursor}] {values}")
+    }
+
+    /// Reduces series that have more points than there are columns on the canvas.
+    /// A streaming window that is wider than the canvas shrinks by the same
+    /// factor as the series so the points keep their place on the right.
+    fn downsample(&mut self, size: Size) {
+        let Some(graph_data) = &mut self.graph_data else {
+            return;
+        };
+
+        let largest_points_len = graph_data.series.iter()
+            .map(|series| series.points.len().max(series.candles.len()))
+            .max()
+            .unwrap_or(0);
+        let capacity = capacity(size, &self.graph_type);
+        let window = self.window.unwrap_or(largest_points_len).max(largest_points_len);
+        if window <= capacity {
+            return;
+        }
+
+        let ratio = capacity as f32 / window as f32;
+        let buckets = |len: usize| ((len as f32 * ratio).ceil() as usize).max(1);
+        for series in graph_data.series.iter_mut() {
+            series.points = self.aggregate.reduce(&series.points, buckets(series.points.len()));
+            series.candles = reduce_candles(&series.candles, buckets(series.candles.len()));
+        }
+
+        if self.window.is_some() {
+            self.window = Some(capacity);
+        }
+    }
+
+    fn update_range(&mut self) {
+        if let Some(graph_data) = &self.graph_data {
+            (self.range, self.x_range) = self.domains(graph_data);
+        }
+    }
+
+    /// The y and x domains that fit the data.
+    /// Candles and boxes are drawn over the range they cover, prices far from
+    /// zero would otherwise be squashed into the top rows.
+    pub(crate) fn domains(&self, graph_data: &GraphData) -> ((f32, f32), (f32, f32)) {
+        let range = match (&self.graph_type, self.stacked) {
+            (GraphType::Candlestick | GraphType::BoxPlot, _) => self.scale.tight_domain(graph_data.values()),
+            (_, true) => self.scale.domain(graph_data.values().chain(graph_data.stacked_values())),
+            (_, false) => self.scale.domain(graph_data.values()),
+        };
+        (range, self.x_scale.tight_domain(graph_data.x_values()))
+    }
+
+    /// Every point that was received, rather than what is drawn: the whole
+    /// history when streaming, and the latest data as it arrived when replacing
+    pub(crate) fn source_data(&self) -> Option<GraphData> {
+        match self.mode {
+            GraphMode::Stream if !self.history.is_empty() => Some(self.history.window(self.history.len(), 0)),
+            GraphMode::Stream => None,
+            GraphMode::Replace => self.received.clone(),
+        }
     }
 }
 
-enum GraphType {
-    Point, Bar
+#[derive(Default)]
+pub(crate) enum GraphType {
+    #[default]
+    Point, Bar, Area, Scatter, Candlestick, BoxPlot
+}
+
+impl GraphType {
+    /// Bars and candles leave the first column free for the y axis
+    fn first_column(&self) -> u16 {
+        match self {
+            GraphType::Bar | GraphType::Candlestick | GraphType::BoxPlot => 1,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Layout {
+    point_width: u16,
+    x_offset: u16,
+    len: usize,
+}
+
+impl Layout {
+    fn column(&self, index: usize, graph_type: &GraphType) -> u16 {
+        graph_type.first_column() + self.x_offset + index as u16 * (self.point_width + 1)
+    }
+
+    fn index(&self, column: u16, graph_type: &GraphType) -> Option<usize> {
+        let column = column.checked_sub(graph_type.first_column() + self.x_offset)?;
+        let index = (column / (self.point_width + 1)) as usize;
+        (index < self.len).then_some(index)
+    }
+}
+
+/// Shared by every series of an area graph
+struct AreaFill {
+    /// How many series cover each cell, to shade overlaps
+    coverage: Vec<u8>,
+    /// The running total of each x when stacked
+    baseline: Vec<f32>,
+}
+
+impl AreaFill {
+    fn new(canvas_size: Size, len: usize) -> Self {
+        Self {
+            coverage: vec![0; canvas_size.width as usize * canvas_size.height as usize],
+            baseline: vec![0.0; len],
+        }
+    }
 }
 
 impl From<&str> for GraphType {
     fn from(value: &str) -> Self {
         match value {
             "bar" => GraphType::Bar,
+            "area" => GraphType::Area,
+            "scatter" => GraphType::Scatter,
+            "candlestick" => GraphType::Candlestick,
+            "box" => GraphType::BoxPlot,
             _ => GraphType::Point,
         }
     }
@@ -100,7 +550,7 @@ impl Graph {
         }
     }
     
-    fn draw_axis(&self, canvas: &mut Canvas, x_axis: Option<char>, y_axis: Option<char>, size: Size) {
+    fn draw_axis(&self, canvas: &mut impl Surface, x_axis: Option<char>, y_axis: Option<char>, size: Size) {
         match x_axis {
             None => {}
             Some(value) => {
@@ -120,34 +570,66 @@ impl Graph {
         }
     }
 
-    fn draw_data_points(&self, canvas: &mut Canvas, markers: &[char], graph_type: &GraphType, canvas_size: Size) {
+    fn draw_cursor(&self, canvas: &mut impl Surface, size: Size) {
+        let (Some(cursor), Some(layout)) = (self.cursor, self.current_layout) else {
+            return;
+        };
+
+        let x = layout.column(cursor, &self.graph_type);
+        if x >= size.width {
+            return;
+        }
+
+        let mut style = Style::new();
+        style.set_fg(Color::DarkGrey);
+        for y in 0..size.height.saturating_sub(1) {
+            canvas.put('┆', style, LocalPos::new(x, y));
+        }
+    }
+
+    fn draw_data_points(&self, canvas: &mut impl Surface, markers: &[char], graph_type: &GraphType, canvas_size: Size) {
         match &self.graph_data {
             None => {}
             Some(graph_data) => {
-                let mut largest_points_len = 0;
-
-                graph_data.series.iter().for_each(|series| {
-                    (series.points.len() > largest_points_len)
-                        .then(|| largest_points_len = series.points.len());
-                });
+                match graph_type {
+                    GraphType::Scatter => {
+                        graph_data.series.iter().enumerate().for_each(|(index, series)| {
+                            self.draw_scatter_graph(canvas_size, canvas, series, Self::determine_marker(markers, index));
+                        });
+                        return;
+                    }
+                    GraphType::Candlestick | GraphType::BoxPlot => {
+                        graph_data.series.iter().enumerate().for_each(|(index, series)| {
+                            self.draw_candle_graph(canvas_size, canvas, &series.candles, graph_type, index);
+                        });
+                        return;
+                    }
+                    GraphType::Point | GraphType::Bar | GraphType::Area => {}
+                }
 
-                if largest_points_len == 0 {
+                let Some(layout) = self.layout(canvas_size) else {
                     return; // No data to draw
-                }
+                };
+                let (bar_width, x_offset) = (layout.point_width, layout.x_offset);
 
-                let mut bar_width = (canvas_size.width as usize / largest_points_len) as u16;
-                if bar_width > 1 {
-                    bar_width -= 1; // Ensure at least one character width for the bar
-                }
+                let mut fill = AreaFill::new(canvas_size, layout.len);
 
                 graph_data.series.iter().enumerate().for_each(|(index, series)| {
                     match graph_type {
-                        GraphType::Point => self.draw_point_graph(bar_width, canvas_size, canvas, &series.points, Self::determine_marker(markers, index)),
+                        GraphType::Point => self.draw_point_graph(bar_width, x_offset, canvas_size, canvas, &series.points, Self::determine_marker(markers, index)),
                         GraphType::Bar => {
                             let mut style = Style::new();
                             style.set_bg(Self::get_bar_colour(index));
-                            self.draw_bar_graph(bar_width, canvas_size, canvas, &series.points, style)
+                            self.draw_bar_graph(bar_width, x_offset, canvas_size, canvas, &series.points, style)
                         },
+                        GraphType::Area => {
+                            let mut style = Style::new();
+                            style.set_fg(Self::get_bar_colour(index));
+                            self.draw_area_graph((bar_width, x_offset), canvas_size, canvas, &series.points, style, &mut fill)
+                        },
+                        GraphType::Scatter | GraphType::Candlestick | GraphType::BoxPlot => {
+                            unreachable!("graphs that are not indexed by point are drawn above")
+                        }
                     }
                 });
             }
@@ -155,21 +637,20 @@ impl Graph {
     }
 
     fn determine_marker(markers: &[char], index: usize) -> char {
-        if index > markers.len() {
-            markers.first().unwrap().to_ascii_lowercase()
-        } else {
-            markers.get(index).unwrap().to_ascii_lowercase()
-        }
+        markers.get(index)
+            .or(markers.first())
+            .map(char::to_ascii_lowercase)
+            .unwrap_or('@')
     }
 
-    fn draw_bar_graph(&self, bar_width: u16, canvas_size: Size, canvas: &mut Canvas, points: &[f32], style: Style) {
-        let mut x = 1;
+    fn draw_bar_graph(&self, bar_width: u16, x_offset: u16, canvas_size: Size, canvas: &mut impl Surface, points: &[f32], style: Style) {
+        let mut x = 1 + x_offset;
 
         points.iter().for_each(|point| {
-            let converted_point = convert_point(canvas_size, self.range, point);
+            let converted_point = convert_point(canvas_size, self.range, point, &self.scale).unwrap_or(0);
             for col in x..(x+bar_width) {
                 for row in 0..converted_point {
-                    canvas.put(' ', style, LocalPos::new(col, canvas_size.height - row));
+                    canvas.put(' ', style, LocalPos::new(col, canvas_size.height - 1 - row));
                 }
             }
             
@@ -177,16 +658,141 @@ impl Graph {
         })
     }
 
-    fn draw_point_graph(&self, point_width: u16, canvas_size: Size, canvas: &mut Canvas, points: &[f32], marker: char) {
-        let mut x = 0;
+    fn draw_point_graph(&self, point_width: u16, x_offset: u16, canvas_size: Size, canvas: &mut impl Surface, points: &[f32], marker: char) {
+        let mut x = x_offset;
 
         points.iter().for_each(| point| {
-            let converted_point = convert_point(canvas_size, self.range, point);
-            canvas.put(marker, Style::reset(), LocalPos::new(x, canvas_size.height - converted_point));
+            // Values the scale can't represent (e.g. zero on a log scale) are skipped
+            if let Some(converted_point) = convert_point(canvas_size, self.range, point, &self.scale) {
+                canvas.put(marker, Style::reset(), LocalPos::new(x, canvas_size.height - converted_point.max(1)));
+            }
             x += point_width + 1; // +1 for the space between points
         })
     }
 
+    fn draw_scatter_graph(&self, canvas_size: Size, canvas: &mut impl Surface, series: &GraphSeries, marker: char) {
+        let x_extent = canvas_size.width.saturating_sub(1);
+
+        series.xy().for_each(|(x, y)| {
+            let column = self.x_scale.project(x, self.x_range, x_extent);
+            let row = convert_point(canvas_size, self.range, &y, &self.scale);
+            if let (Some(column), Some(row)) = (column, row) {
+                canvas.put(marker, Style::reset(), LocalPos::new(column, canvas_size.height - row.max(1)));
+            }
+        })
+    }
+
+    /// Fills the area between the baseline and a line through the points.
+    /// Overlapping series are drawn with denser shades so they look translucent,
+    /// stacked series start where the previous series ended.
+    fn draw_area_graph(&self, (point_width, x_offset): (u16, u16), canvas_size: Size, canvas: &mut impl Surface, points: &[f32], style: Style, fill: &mut AreaFill) {
+        let AreaFill { coverage, baseline } = fill;
+        let step = point_width + 1;
+        let to_rows = |value: f32| convert_point(canvas_size, self.range, &value, &self.scale).unwrap_or(0);
+
+        for (index, point) in points.iter().enumerate() {
+            let next = points.get(index + 1);
+            let next_base = baseline.get(index + 1).copied().unwrap_or(baseline[index]);
+            // The last point only covers its own column
+            let columns = if next.is_some() { step } else { point_width.max(1) };
+
+            for offset in 0..columns {
+                let x = x_offset + index as u16 * step + offset;
+                if x >= canvas_size.width {
+                    return;
+                }
+
+                let t = offset as f32 / step as f32;
+                let value = next.map(|next| point + (next - point) * t).unwrap_or(*point);
+                let (bottom, top) = match self.stacked {
+                    true => {
+                        let base = baseline[index] + (next_base - baseline[index]) * t;
+                        (base, base + value)
+                    }
+                    false => (0.0, value),
+                };
+
+                let (bottom, top) = (to_rows(bottom), to_rows(top));
+                for row in bottom.min(top)..bottom.max(top) {
+                    let y = canvas_size.height - 1 - row;
+                    let cell = &mut coverage[y as usize * canvas_size.width as usize + x as usize];
+                    *cell = cell.saturating_add(1);
+                    let shade = match (self.stacked, *cell) {
+                        (true, _) => '▓',
+                        (false, 1) => '░',
+                        (false, 2) => '▒',
+                        _ => '▓',
+                    };
+                    canvas.put(shade, style, LocalPos::new(x, y));
+                }
+            }
+        }
+
+        if self.stacked {
+            points.iter().enumerate().for_each(|(index, point)| baseline[index] += point);
+        }
+    }
+
+    /// Draws a wick from low to high with a body between open and close.
+    /// Candlesticks are coloured by direction, box plots by series.
+    fn draw_candle_graph(&self, canvas_size: Size, canvas: &mut impl Surface, candles: &[Candle], graph_type: &GraphType, index: usize) {
+        if candles.is_empty() {
+            return;
+        }
+
+        let mut candle_width = (canvas_size.width as usize / candles.len()) as u16;
+        if candle_width > 1 {
+            candle_width -= 1; // Leave a gap between candles
+        }
+        let candle_width = candle_width.max(1);
+
+        let to_y = |value: f32| {
+            convert_point(canvas_size, self.range, &value, &self.scale)
+                .map(|rows| canvas_size.height - rows.max(1))
+        };
+
+        let mut x = 1;
+        for candle in candles {
+            if x >= canvas_size.width {
+                break;
+            }
+
+            let colour = match graph_type {
+                GraphType::BoxPlot => Self::get_bar_colour(index),
+                _ if candle.close >= candle.open => Color::Green,
+                _ => Color::Red,
+            };
+            let mut style = Style::new();
+            style.set_fg(colour);
+
+            let right = (x + candle_width).min(canvas_size.width);
+            let centre = x + (right - x) / 2;
+
+            if let (Some(high), Some(low)) = (to_y(candle.high), to_y(candle.low)) {
+                for y in high..=low {
+                    canvas.put('│', style, LocalPos::new(centre, y));
+                }
+
+                if let GraphType::BoxPlot = graph_type {
+                    for col in x..right {
+                        canvas.put('─', style, LocalPos::new(col, high));
+                        canvas.put('─', style, LocalPos::new(col, low));
+                    }
+                }
+            }
+
+            if let (Some(open), Some(close)) = (to_y(candle.open), to_y(candle.close)) {
+                for y in open.min(close)..=open.max(close) {
+                    for col in x..right {
+                        canvas.put('█', style, LocalPos::new(col, y));
+                    }
+                }
+            }
+
+            x += candle_width + 1;
+        }
+    }
+
     fn get_bar_colour(index: usize) -> Color {
        match index % 4 {
            0 => Color::Blue,
@@ -201,61 +807,135 @@ impl Component for Graph {
     type State = GraphDataState;
     type Message = ();
 
-    fn on_tick(&mut self, state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, _dt: Duration) {
-        let mut file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open("log.log")
-            .unwrap();
-
-        // write!(file, "{:?}", context.attributes.get("data")).unwrap();
-
-        if let Some(attribute_data) = context.attributes.get("data") &&
-            let Some(data) = attribute_data.as_list() {
-            let mut graph_data: GraphData = GraphData::default();
-
-            for series in data.iter() {
-                match series {
-                   ValueKind::List(data_points) => {
-                        let points = data_points.iter()
-                            .map(|point| point.as_float().unwrap() as f32)
-                            .collect();
-                       graph_data.series.push(GraphSeries { points });
-                   }
-                    ValueKind::DynList(data_points) => {
-                        // write!(file, "{:?}", data_points).unwrap();
-                        let points= data_points.as_state().unwrap().as_any_list().unwrap().iter()
-                            .filter(|point| point.as_state().unwrap().as_float().is_some())
-                            .map(|point| point.as_state().unwrap().as_float().unwrap() as f32)
-                            .collect();
-                        graph_data.series.push(GraphSeries { points });
-                    }
-                    ValueKind::Null => {
-                    }
-                    _ => {
-                        panic!("Unexpected data type");
-                    }
-                }
-            }
-
-            self.range = determine_largest_range_in_series(&graph_data);
-            self.graph_data = Some(graph_data);
-        }
+    fn on_tick(&mut self, state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, dt: Duration) {
+        self.update(&context, context.attributes.get("data"), dt);
 
         children.elements().by_tag("canvas")
             .first(|el, _| {
                 let size = el.size();
+                self.prepare(size);
                 self.clear_canvas(el.to::<Canvas>(), size);
                 self.calculate_state(state, el);
             });
 
-        self.draw_graph(&mut children, context);
+        state.tooltip.set(self.tooltip());
+        state.view_range.set(self.view_range());
+        self.draw_graph(&mut children);
+    }
+
+    fn on_key(&mut self, key: KeyEvent, state: &mut Self::State, _children: Children<'_, '_>, _context: Context<'_, '_, Self::State>) {
+        if self.move_cursor(key.code) {
+            state.tooltip.set(self.tooltip());
+        }
+
+        // The window is rebuilt from the history on the next tick
+        self.move_view(key.code);
+
+        if let KeyCode::Char('e') = key.code {
+            let status = match export::export(self, &self.export_path) {
+                Ok(()) => format!("exported {0}.txt, {0}.csv and {0}.svg", self.export_path),
+                Err(error) => format!("export failed: {error}"),
+            };
+            state.export_status.set(status);
+        }
+    }
+
+    fn on_mouse(&mut self, mouse: MouseEvent, state: &mut Self::State, mut children: Children<'_, '_>, _context: Context<'_, '_, Self::State>) {
+        let Some(layout) = self.current_layout else {
+            return;
+        };
+        if !mouse.lsb_down() {
+            return;
+        }
+
+        children.elements().by_tag("canvas")
+            .first(|el, _| {
+                // Clicks on the labels or on other widgets leave the cursor alone
+                if let Some((column, _)) = canvas_cell(mouse.pos(), el.get_pos(), el.size()) &&
+                    let Some(index) = layout.index(column, &self.graph_type) {
+                    self.cursor = Some(index);
+                }
+            });
+
+        state.tooltip.set(self.tooltip());
     }
 
     fn on_resize(&mut self, state: &mut Self::State, _children: Children<'_, '_>, _context: Context<'_, '_, Self::State>) {
         state.updated.set(true);
     }
 }
+
+/// The column and row of the canvas under the mouse, if the mouse is over the canvas
+pub(crate) fn canvas_cell(mouse: Pos, canvas: Pos, size: Size) -> Option<(u16, u16)> {
+    let (column, row) = (mouse.x - canvas.x, mouse.y - canvas.y);
+    let inside = (0..size.width as i32).contains(&column) && (0..size.height as i32).contains(&row);
+    inside.then_some((column as u16, row as u16))
+}
+
+/// Reads the `data` attribute, a list of series
+fn parse_data(data: &ValueKind, box_plot: bool) -> Option<GraphData> {
+    let data = data.as_list()?;
+    let mut graph_data: GraphData = GraphData::default();
+
+    for series in data.iter() {
+        match series {
+           ValueKind::List(data_points) => {
+               graph_data.series.push(parse_series(data_points, box_plot));
+           }
+            ValueKind::DynList(data_points) => {
+                let points= data_points.as_state().unwrap().as_any_list().unwrap().iter()
+                    .filter(|point| point.as_state().unwrap().as_float().is_some())
+                    .map(|point| point.as_state().unwrap().as_float().unwrap() as f32)
+                    .collect();
+                graph_data.series.push(GraphSeries { points, ..Default::default() });
+            }
+            ValueKind::Null => {
+            }
+            _ => {
+                panic!("Unexpected data type");
+            }
+        }
+    }
+    Some(graph_data)
+}
+
+/// Parses a literal series, which is either a list of values, a list of
+/// two-element `[x, y]` lists or a list of four-element
+/// `[open, high, low, close]` lists (`[min, p50, p99, max]` for box plots).
+fn parse_series(data_points: &[ValueKind], box_plot: bool) -> GraphSeries {
+    let mut series = GraphSeries::default();
+    for point in data_points.iter() {
+        match point {
+            ValueKind::List(pair) if pair.len() == 2 => {
+                if let (Some(x), Some(y)) = (as_f32(&pair[0]), as_f32(&pair[1])) {
+                    series.pairs.push((x, y));
+                }
+            }
+            ValueKind::List(tuple) if tuple.len() == 4 => {
+                let values: Vec<f32> = tuple.iter().filter_map(as_f32).collect();
+                if let [first, second, third, fourth] = values[..] {
+                    series.candles.push(match box_plot {
+                        true => Candle::from_box(first, second, third, fourth),
+                        false => Candle { open: first, high: second, low: third, close: fourth },
+                    });
+                }
+            }
+            _ => {
+                if let Some(value) = as_f32(point) {
+                    series.points.push(value);
+                }
+            }
+        }
+    }
+    series
+}
+
+pub(crate) fn as_f32(value: &ValueKind) -> Option<f32> {
+    value.as_float()
+        .or_else(|| value.as_int().map(|value| value as f64))
+        .map(|value| value as f32)
+}
+
 pub fn calculate_point_width(series: &GraphSeries, size: Size) -> u16 {
     let range = series.points.len() as f32;
     if range > 0.0 {
@@ -265,8 +945,18 @@ pub fn calculate_point_width(series: &GraphSeries, size: Size) -> u16 {
     }
 }
 
-pub fn convert_point(size: Size, range: (f32, f32), point: &f32) -> u16 {
-    ((*point / (range.1-range.0)) * size.height as f32) as u16
+/// How many points fit next to each other on the canvas.
+/// Every point takes at least one column plus the gap `layout` keeps after it,
+/// except for the last point which has nothing after it.
+fn capacity(size: Size, graph_type: &GraphType) -> usize {
+    let columns = size.width.saturating_sub(graph_type.first_column()) as usize;
+    columns.div_ceil(2).max(1)
+}
+
+/// Converts a point into the number of rows it covers on the canvas.
+/// Returns `None` if the scale can not represent the point.
+pub fn convert_point(size: Size, range: (f32, f32), point: &f32, scale: &Scale) -> Option<u16> {
+    scale.project(*point, range, size.height)
 }
 
 /// This function determines the smallest and largest values in the series of points
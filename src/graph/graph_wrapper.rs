@@ -1,11 +1,16 @@
 use std::time::Duration;
 use anathema::component::{Children, Component, Context};
 use anathema::resolver::ValueKind;
+use rand::Rng;
+use crate::attributes::attributes::Attributes;
 use crate::graph::graph::{GraphData, GraphSeries};
+use crate::random::seed::SeededRng;
+use crate::timing::interval::Interval;
 
 pub struct GraphWrapper {
-    pub last_update: Option<Duration>,
+    update: Interval,
     pub series: Option<GraphData>,
+    rng: SeededRng,
 }
 
 impl Component for GraphWrapper {
@@ -13,14 +18,13 @@ impl Component for GraphWrapper {
     type Message = ();
 
     fn on_tick(&mut self, _state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, dt: Duration) {
-        if let Some(last_update) = self.last_update {
-            if dt.as_millis() - last_update.as_millis() < 1000 {
-                return; // Skip update if less than 1 second has passed
-            }
+        if !self.update.ready(dt) {
+            return; // Skip update if less than 1 second has passed
         }
 
-        let series_count = context.attributes.get_as::<u8>("series_count").unwrap_or(1);
-        let data_count = context.attributes.get_as::<u8>("data_count").unwrap_or(10);
+        let series_count = context.integer("series_count").unwrap_or(1) as u8;
+        let data_count = context.integer("data_count").unwrap_or(10) as u8;
+        self.rng.reseed(context.integer("seed").map(|seed| seed as u64));
         self.update_random_data(series_count, data_count);
 
         if let Some(data) = &self.series {
@@ -36,23 +40,25 @@ impl Component for GraphWrapper {
 
 impl GraphWrapper {
     pub fn new() -> Self {
+        let mut rng = SeededRng::default();
         Self {
-            last_update: None,
-            series: generate_random_series(1, 10),
+            update: Interval::new(Duration::from_secs(1)),
+            series: generate_random_series(rng.rng(), 1, 10),
+            rng,
         }
     }
 
     fn update_random_data(&mut self, series_count: u8, data_count: u8) {
-        self.series = generate_random_series(series_count, data_count);
+        self.series = generate_random_series(self.rng.rng(), series_count, data_count);
     }
 }
 
-fn generate_random_series(series_count: u8, data_count: u8) -> Option<GraphData> {
+pub(crate) fn generate_random_series(rng: &mut impl Rng, series_count: u8, data_count: u8) -> Option<GraphData> {
     let mut series = GraphData::default();
     for _ in 0..series_count {
         let mut data_points = GraphSeries::default();
         for _ in 0..data_count {
-            data_points.points.push(rand::random::<f32>() * 100.0);
+            data_points.points.push(rng.random::<f32>() * 100.0);
         }
         series.series.push(data_points);
     }
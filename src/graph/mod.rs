@@ -1,2 +1,7 @@
+pub mod downsample;
+pub mod export;
 pub mod graph;
-pub mod graph_wrapper;
\ No newline at end of file
+pub mod graph_wrapper;
+pub mod scale;
+pub mod stream;
+pub mod transition;
@@ -0,0 +1,209 @@
+/// The transform applied to values before they are mapped onto the canvas.
+/// Both the point conversion and the y axis labels go through the same scale
+/// so the ticks always line up with the data.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Scale {
+    #[default]
+    Linear,
+    Log,
+    Sqrt,
+}
+
+impl From<&str> for Scale {
+    fn from(value: &str) -> Self {
+        match value {
+            "log" => Scale::Log,
+            "sqrt" => Scale::Sqrt,
+            _ => Scale::Linear,
+        }
+    }
+}
+
+impl Scale {
+    /// Moves a value into scale space.
+    /// Returns `None` for values the scale cannot represent, i.e. zero or
+    /// negative values on a log scale.
+    pub fn transform(&self, value: f32) -> Option<f32> {
+        match self {
+            Scale::Linear => Some(value),
+            Scale::Log => (value > 0.0).then(|| value.log10()),
+            // Signed square root so negative values are still representable
+            Scale::Sqrt => Some(value.signum() * value.abs().sqrt()),
+        }
+    }
+
+    /// Determines the domain to plot for the given values.
+    /// Linear and sqrt scales always include zero as the baseline, the log scale
+    /// ignores anything that is not positive and snaps to whole decades.
+    pub fn domain(&self, values: impl Iterator<Item = f32>) -> (f32, f32) {
+        match self {
+            Scale::Linear | Scale::Sqrt => values.fold((0.0, 0.0), |(smallest, largest), value| {
+                (smallest.min(value), largest.max(value))
+            }),
+            Scale::Log => {
+                let (smallest, largest) = values
+                    .filter(|value| *value > 0.0)
+                    .fold((f32::MAX, f32::MIN), |(smallest, largest), value| {
+                        (smallest.min(value), largest.max(value))
+                    });
+
+                if smallest > largest {
+                    return (1.0, 10.0); // Nothing positive to plot
+                }
+
+                let low = smallest.log10().floor();
+                let mut high = largest.log10().ceil();
+                if high <= low {
+                    high = low + 1.0;
+                }
+                (10f32.powf(low), 10f32.powf(high))
+            }
+        }
+    }
+
+    /// Like `domain` but without forcing zero into the range, used for the x
+    /// axis of scatter graphs where the data rarely starts at zero.
+    pub fn tight_domain(&self, values: impl Iterator<Item = f32>) -> (f32, f32) {
+        match self {
+            Scale::Log => self.domain(values),
+            Scale::Linear | Scale::Sqrt => {
+                let (smallest, largest) = values.fold((f32::MAX, f32::MIN), |(smallest, largest), value| {
+                    (smallest.min(value), largest.max(value))
+                });
+
+                if smallest > largest {
+                    (0.0, 1.0)
+                } else {
+                    (smallest, largest)
+                }
+            }
+        }
+    }
+
+    /// Projects a value onto `0..=extent` cells for the given domain.
+    pub fn project(&self, value: f32, domain: (f32, f32), extent: u16) -> Option<u16> {
+        let value = self.transform(value)?;
+        let low = self.transform(domain.0)?;
+        let high = self.transform(domain.1)?;
+
+        let span = high - low;
+        if span <= f32::EPSILON {
+            return Some(0);
+        }
+
+        let ratio = ((value - low) / span).clamp(0.0, 1.0);
+        Some((ratio * extent as f32).round() as u16)
+    }
+
+    /// Generates roughly `count` tick values within the domain.
+    pub fn ticks(&self, domain: (f32, f32), count: usize) -> Vec<f32> {
+        let count = count.max(2);
+        match self {
+            Scale::Linear => linear_ticks(domain, count),
+            // Evenly spaced in scale space, so the ticks get their own rows
+            Scale::Sqrt => {
+                let (Some(low), Some(high)) = (self.transform(domain.0), self.transform(domain.1)) else {
+                    return vec![];
+                };
+
+                linear_ticks((low, high), count)
+                    .into_iter()
+                    .map(|tick| tick * tick.abs())
+                    .collect()
+            }
+            Scale::Log => {
+                let (Some(low), Some(high)) = (self.transform(domain.0), self.transform(domain.1)) else {
+                    return vec![];
+                };
+
+                let low = low.floor() as i32;
+                let high = high.ceil() as i32;
+                let decades = (high - low + 1) as usize;
+                let skip = decades.div_ceil(count).max(1);
+
+                (low..=high)
+                    .step_by(skip)
+                    .map(|exponent| 10f32.powi(exponent))
+                    .collect()
+            }
+        }
+    }
+
+    /// Produces one label per row of a canvas with the given height.
+    /// Rows without a tick are left empty and all labels share the same width
+    /// so they can be stacked next to the canvas.
+    pub fn labels(&self, domain: (f32, f32), height: u16) -> Vec<String> {
+        let mut labels = vec![String::new(); height as usize];
+        if height == 0 {
+            return labels;
+        }
+
+        for tick in self.ticks(domain, (height / 3) as usize) {
+            let Some(rows) = self.project(tick, domain, height) else {
+                continue;
+            };
+            let row = height.saturating_sub(rows.max(1)) as usize;
+            labels[row] = format_tick(tick);
+        }
+
+        let width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+        labels
+            .into_iter()
+            .map(|label| format!("{label:>width$}"))
+            .collect()
+    }
+}
+
+/// Ticks on multiples of a nice step
+fn linear_ticks(domain: (f32, f32), count: usize) -> Vec<f32> {
+    let span = domain.1 - domain.0;
+    if span <= f32::EPSILON {
+        return vec![domain.0];
+    }
+
+    let step = nice_step(span / (count - 1) as f32);
+    let mut tick = (domain.0 / step).ceil() * step;
+    let mut ticks = vec![];
+    while tick <= domain.1 + step * 0.001 {
+        // Avoid printing -0
+        ticks.push(if tick.abs() < step * 0.001 { 0.0 } else { tick });
+        tick += step;
+    }
+    ticks
+}
+
+/// Rounds a raw step to 1, 2 or 5 times a power of ten
+fn nice_step(raw: f32) -> f32 {
+    let magnitude = 10f32.powf(raw.log10().floor());
+    let normalised = raw / magnitude;
+    let nice = if normalised <= 1.0 {
+        1.0
+    } else if normalised <= 2.0 {
+        2.0
+    } else if normalised <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+pub(crate) fn format_tick(value: f32) -> String {
+    let abs = value.abs();
+    if abs >= 1_000_000_000.0 {
+        format!("{}G", trim(value / 1_000_000_000.0))
+    } else if abs >= 1_000_000.0 {
+        format!("{}M", trim(value / 1_000_000.0))
+    } else if abs >= 1_000.0 {
+        format!("{}k", trim(value / 1_000.0))
+    } else if abs != 0.0 && abs < 0.01 {
+        format!("{value:.0e}")
+    } else {
+        trim(value)
+    }
+}
+
+fn trim(value: f32) -> String {
+    let formatted = format!("{value:.2}");
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use crate::graph::graph::{GraphData, GraphSeries};
+use crate::timing::interval::Interval;
+
+/// How many points are kept per series when streaming
+pub const HISTORY_LENGTH: usize = 1000;
+/// How often a sample is taken when streaming, unless `sample_interval` says otherwise
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Whether new data replaces what is drawn or is appended to it
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GraphMode {
+    #[default]
+    Replace,
+    Stream,
+}
+
+impl From<&str> for GraphMode {
+    fn from(value: &str) -> Self {
+        match value {
+            "stream" => GraphMode::Stream,
+            _ => GraphMode::Replace,
+        }
+    }
+}
+
+/// The points received so far for every series when streaming
+pub struct History {
+    series: Vec<VecDeque<f32>>,
+    sample: Interval,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            series: vec![],
+            sample: Interval::new(SAMPLE_INTERVAL),
+        }
+    }
+}
+
+impl History {
+    pub fn set_sample_interval(&mut self, interval: Duration) {
+        self.sample.set_period(interval);
+    }
+
+    /// Appends the points of every series once per sample interval.
+    /// The data attribute is read every tick whether or not it changed, so the
+    /// timeline is sampled at a fixed rate and a value that holds steady keeps
+    /// scrolling like any other.
+    pub fn push(&mut self, graph_data: &GraphData, dt: Duration) {
+        if !self.sample.ready(dt) {
+            return;
+        }
+
+        self.series.resize_with(graph_data.series.len(), VecDeque::new);
+        for (history, series) in self.series.iter_mut().zip(graph_data.series.iter()) {
+            history.extend(series.points.iter().copied());
+            while history.len() > HISTORY_LENGTH {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// Number of points in the longest series
+    pub fn len(&self) -> usize {
+        self.series.iter().map(VecDeque::len).max().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `length` points of every series, ending `offset` points before the most
+    /// recent point
+    pub fn window(&self, length: usize, offset: usize) -> GraphData {
+        GraphData {
+            series: self.series.iter()
+                .map(|history| {
+                    let end = history.len().saturating_sub(offset);
+                    GraphSeries {
+                        points: history.range(end.saturating_sub(length)..end).copied().collect(),
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+use crate::graph::graph::GraphData;
+use crate::timing::easing::Easing;
+use crate::timing::tween::Tween;
+
+/// Eases every point from the value that was drawn to the value that arrived,
+/// instead of jumping to it
+#[derive(Default)]
+pub struct Transition {
+    pub duration: Duration,
+    pub easing: Easing,
+    /// A tween for every point of every series
+    tweens: Vec<Vec<Tween>>,
+}
+
+impl Transition {
+    pub fn is_enabled(&self) -> bool {
+        !self.duration.is_zero()
+    }
+
+    /// Heads for the points that arrived.
+    /// Points that were not there before appear at their value straight away.
+    pub fn retarget(&mut self, graph_data: &GraphData) {
+        self.tweens.resize_with(graph_data.series.len(), Vec::new);
+
+        for (tweens, series) in self.tweens.iter_mut().zip(graph_data.series.iter()) {
+            tweens.truncate(series.points.len());
+            for (index, point) in series.points.iter().enumerate() {
+                match tweens.get_mut(index) {
+                    Some(tween) => {
+                        tween.set_duration(self.duration);
+                        tween.set_easing(self.easing);
+                        tween.retarget(*point);
+                    }
+                    None => tweens.push(Tween::at(*point, self.duration, self.easing)),
+                }
+            }
+        }
+    }
+
+    pub fn advance(&mut self, dt: Duration) {
+        self.tweens.iter_mut()
+            .flatten()
+            .for_each(|tween| tween.advance(dt));
+    }
+
+    /// Moves every point to where its tween is now
+    pub fn apply(&self, graph_data: &mut GraphData) {
+        for (tweens, series) in self.tweens.iter().zip(graph_data.series.iter_mut()) {
+            for (tween, point) in tweens.iter().zip(series.points.iter_mut()) {
+                *point = tween.value();
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+use anathema::component::{Children, Component, Context};
+use anathema::default_widgets::Canvas;
+use anathema::geometry::Size;
+use anathema::resolver::ValueKind;
+use crate::attributes::attributes::Attributes;
+use crate::effects::palette::Palette;
+use crate::graph::graph::as_f32;
+
+/// Renders a grid of values as coloured cells.
+/// Every canvas cell holds two rows of the grid by drawing an upper half block
+/// with the top row as the foreground and the bottom row as the background.
+#[derive(Default)]
+pub struct Heatmap {
+    pub(crate) rows: Vec<Vec<f32>>,
+}
+
+impl Heatmap {
+    fn read_rows(&mut self, data: Option<&ValueKind>) {
+        if let Some(rows) = data.and_then(parse_rows) {
+            self.rows = rows;
+        }
+    }
+
+    /// The `min` and `max` attributes, or the smallest and largest value
+    fn range(&self, attributes: &impl Attributes) -> (f32, f32) {
+        let (smallest, largest) = self.rows.iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(smallest, largest), value| {
+                (smallest.min(*value), largest.max(*value))
+            });
+
+        let min = attributes.number("min").unwrap_or(smallest);
+        let max = attributes.number("max").unwrap_or(largest);
+        (min, max)
+    }
+
+    fn draw(&self, canvas: &mut Canvas, size: Size, palette: &Palette, range: (f32, f32)) {
+        let columns = self.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        if self.rows.is_empty() || columns == 0 {
+            return;
+        }
+
+        let half_rows = size.height as usize * 2;
+        palette.draw_pixels(canvas, size, |x, half_row| {
+            self.cell(half_row as usize, x as usize, half_rows, columns, size.width as usize)
+                .map(|value| normalise(value, range))
+        });
+    }
+
+    /// Finds the value for a half row and column of the canvas.
+    /// The grid is stretched to fill the canvas, unless there are more columns
+    /// than fit in which case only the most recent columns are shown.
+    /// When there are more rows than half rows, the half row shows the largest
+    /// value of the rows that fall into it.
+    pub(crate) fn cell(&self, half_row: usize, x: usize, half_rows: usize, columns: usize, width: usize) -> Option<f32> {
+        let first = half_row * self.rows.len() / half_rows;
+        let last = ((half_row + 1) * self.rows.len() / half_rows).max(first + 1);
+        let column = if columns > width {
+            columns - width + x
+        } else {
+            x * columns / width
+        };
+
+        self.rows[first..last].iter()
+            .filter_map(|row| row.get(column).copied())
+            .reduce(f32::max)
+    }
+}
+
+/// Reads the `data` attribute, a list of rows of values
+pub(crate) fn parse_rows(data: &ValueKind) -> Option<Vec<Vec<f32>>> {
+    let ValueKind::List(rows) = data else {
+        return None;
+    };
+
+    Some(rows.iter()
+        .map(|row| match row {
+            ValueKind::List(values) => values.iter().filter_map(as_f32).collect(),
+            _ => vec![],
+        })
+        .collect())
+}
+
+fn normalise(value: f32, range: (f32, f32)) -> f32 {
+    let span = range.1 - range.0;
+    if span <= f32::EPSILON {
+        0.0
+    } else {
+        (value - range.0) / span
+    }
+}
+
+impl Component for Heatmap {
+    type State = ();
+    type Message = ();
+
+    fn on_tick(&mut self, _state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, _dt: Duration) {
+        self.read_rows(context.attributes.get("data"));
+
+        let palette = Palette::from_attributes(&context, "heat");
+        let range = self.range(&context);
+
+        children.elements().by_tag("canvas")
+            .first(|el, _| {
+                let size = el.size();
+                self.draw(el.to::<Canvas>(), size, &palette, range);
+            });
+    }
+}
@@ -0,0 +1,83 @@
+/// Upper bound on the number of bins, whatever the strategy asks for
+const MAX_BINS: usize = 200;
+const DEFAULT_BINS: usize = 10;
+
+/// How raw samples are split into bins
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Binning {
+    /// A fixed number of equally wide bins
+    Count(usize),
+    /// Bins of a fixed width
+    Width(f32),
+    /// Bin width chosen from the interquartile range and the number of samples
+    FreedmanDiaconis,
+}
+
+impl Default for Binning {
+    fn default() -> Self {
+        Binning::Count(DEFAULT_BINS)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Bins {
+    /// `counts.len() + 1` edges, the last edge is inclusive
+    pub edges: Vec<f32>,
+    pub counts: Vec<usize>,
+}
+
+impl Binning {
+    pub fn bin(&self, samples: &[f32]) -> Bins {
+        let mut sorted: Vec<f32> = samples.iter().copied().filter(|sample| sample.is_finite()).collect();
+        if sorted.is_empty() {
+            return Bins::default();
+        }
+        sorted.sort_by(f32::total_cmp);
+
+        let min = sorted[0];
+        let max = sorted[sorted.len() - 1];
+        let span = max - min;
+
+        let bin_count = if span <= f32::EPSILON {
+            1
+        } else {
+            match self {
+                Binning::Count(count) => *count,
+                Binning::Width(width) => count_for_width(span, *width),
+                Binning::FreedmanDiaconis => {
+                    let iqr = quantile(&sorted, 0.75) - quantile(&sorted, 0.25);
+                    let width = 2.0 * iqr / (sorted.len() as f32).cbrt();
+                    count_for_width(span, width)
+                }
+            }
+        }.clamp(1, MAX_BINS);
+
+        let width = if span <= f32::EPSILON { 1.0 } else { span / bin_count as f32 };
+        let edges = (0..=bin_count).map(|index| min + width * index as f32).collect();
+
+        let mut counts = vec![0; bin_count];
+        for sample in sorted {
+            let index = (((sample - min) / width) as usize).min(bin_count - 1);
+            counts[index] += 1;
+        }
+
+        Bins { edges, counts }
+    }
+}
+
+fn count_for_width(span: f32, width: f32) -> usize {
+    if width <= 0.0 || !width.is_finite() {
+        DEFAULT_BINS
+    } else {
+        (span / width).ceil() as usize
+    }
+}
+
+/// Linearly interpolated quantile of already sorted samples
+fn quantile(sorted: &[f32], q: f32) -> f32 {
+    let position = q * (sorted.len() - 1) as f32;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f32;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+}
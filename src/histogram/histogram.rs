@@ -0,0 +1,52 @@
+use std::time::Duration;
+use anathema::component::{Children, Component, Context};
+use anathema::resolver::ValueKind;
+use anathema::state::{State, Value};
+use crate::attributes::attributes::Attributes;
+use crate::histogram::binning::{Binning, Bins};
+
+/// Bins raw samples and hands the counts to an embedded `@graph` to be drawn
+/// as a bar graph.
+#[derive(Default)]
+pub struct Histogram {
+    bins: Bins,
+}
+
+#[derive(State, Default)]
+pub struct HistogramState {
+    pub sample_count: Value<u32>,
+}
+
+impl Histogram {
+    pub(crate) fn read_binning(attributes: &impl Attributes) -> Binning {
+        let bins = attributes.integer("bins");
+        match attributes.text("binning").unwrap_or("count") {
+            "width" => attributes.number("bin_width")
+                .map(Binning::Width)
+                .unwrap_or_default(),
+            "fd" => Binning::FreedmanDiaconis,
+            _ => bins.map(|bins| Binning::Count(bins.max(0) as usize)).unwrap_or_default(),
+        }
+    }
+}
+
+impl Component for Histogram {
+    type State = HistogramState;
+    type Message = ();
+
+    fn on_tick(&mut self, state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, _dt: Duration) {
+        let samples = context.numbers("data").unwrap_or_default();
+        self.bins = Self::read_binning(&context).bin(&samples);
+
+        state.sample_count.set(samples.len() as u32);
+
+        children.components().by_name("graph")
+            .first(|_, _, attrs| {
+                attrs.set("data", ValueKind::List(vec![
+                    ValueKind::List(self.bins.counts.iter().map(|count| ValueKind::Float(*count as f64)).collect())
+                ].into()));
+                // The graph labels the edges under the bars
+                attrs.set("x_edges", ValueKind::List(self.bins.edges.iter().map(|edge| ValueKind::Float(*edge as f64)).collect()));
+            });
+    }
+}
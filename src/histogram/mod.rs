@@ -0,0 +1,2 @@
+pub mod binning;
+pub mod histogram;
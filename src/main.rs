@@ -1,13 +1,26 @@
 mod graph;
 mod starfield;
 mod resources;
+mod histogram;
+mod colour;
+mod heatmap;
+mod gauge;
+mod pie;
+mod surface;
+mod attributes;
+mod random;
+mod effects;
+mod timing;
+#[cfg(test)]
+mod testing;
 
+use std::collections::HashSet;
 use std::thread;
 use std::time::Duration;
 use anathema::component::{ComponentId, Emitter};
 use anathema::prelude::{Backend, Document, TuiBackend};
 use anathema::runtime::Runtime;
-use sysinfo::System;
+use sysinfo::{Disk, Disks, System};
 use graph::graph_wrapper::GraphWrapper;
 use crate::resources::cpus::CpusMessage;
 
@@ -18,6 +31,7 @@ fn main() {
         .enable_alt_screen()
         .enable_raw_mode()
         .hide_cursor()
+        .enable_mouse()
         .finish()
         .unwrap();
     backend.finalize();
@@ -30,6 +44,13 @@ fn main() {
 
     builder.component("starfield", "templates/starfield.aml", starfield::starfield::Starfield::default(), starfield::starfield::StarfieldState::default()).unwrap();
     builder.prototype("graph", "templates/graph.aml", graph::graph::Graph::default, graph::graph::GraphDataState::default).unwrap();
+    builder.prototype("histogram", "templates/histogram.aml", histogram::histogram::Histogram::default, histogram::histogram::HistogramState::default).unwrap();
+    builder.prototype("heatmap", "templates/heatmap.aml", heatmap::heatmap::Heatmap::default, || ()).unwrap();
+    builder.prototype("gauge", "templates/gauge.aml", gauge::gauge::Gauge::default, gauge::gauge::GaugeState::default).unwrap();
+    builder.prototype("pie", "templates/pie.aml", pie::pie::Pie::default, || ()).unwrap();
+    builder.prototype("plasma", "templates/plasma.aml", effects::plasma::Plasma::default, || ()).unwrap();
+    builder.prototype("fire", "templates/fire.aml", effects::fire::Fire::default, || ()).unwrap();
+    builder.prototype("matrix", "templates/matrix.aml", effects::matrix::Matrix::default, || ()).unwrap();
     builder.component("graph_wrapper", "templates/graph_wrapper.aml", GraphWrapper::new(), ()).unwrap();
     let thread_id = builder.component("cpus", "templates/resources/cpus.aml", resources::cpus::Cpus::default(), resources::cpus::CpusState::default()).unwrap();
 
@@ -43,19 +64,56 @@ fn main() {
 fn run_thread(emitter: Emitter, thread_id: ComponentId<CpusMessage>) {
     thread::spawn(move || {
         let mut system = System::new_all();
+        let mut disks = Disks::new_with_refreshed_list();
 
         loop {
             system.refresh_cpu_usage();
+            system.refresh_memory();
+            disks.refresh(true);
 
             let thread_info: Vec<f32> = system.cpus()
                 .iter()
                 .map(|cpu| cpu.cpu_usage())
                 .collect();
 
+            let physical_disks = physical_disks(&disks);
+            let disk_mounts = physical_disks.iter()
+                .map(|disk| (
+                    disk.mount_point().to_string_lossy().to_string(),
+                    disk.total_space().saturating_sub(disk.available_space()),
+                ))
+                .collect();
+
+            let (disk_used, disk_total) = physical_disks.iter()
+                .fold((0, 0), |(used, total), disk| {
+                    (used + disk.total_space().saturating_sub(disk.available_space()), total + disk.total_space())
+                });
+
             let _ = emitter.emit(thread_id, CpusMessage {
                 cpu_usage: thread_info,
+                total_usage: system.global_cpu_usage(),
+                memory: (system.used_memory(), system.total_memory()),
+                memory_cache: system.available_memory().saturating_sub(system.free_memory()),
+                memory_free: system.free_memory(),
+                disk: (disk_used, disk_total),
+                disk_mounts,
             });
             thread::sleep(Duration::from_millis(100));
         }
     });
 }
+
+/// Filesystems that are not backed by a device of their own. Their names are
+/// shared, every `tmpfs` is called `tmpfs`, so they can't be told apart by name.
+const VIRTUAL_FILE_SYSTEMS: [&str; 6] = ["tmpfs", "devtmpfs", "ramfs", "overlay", "squashfs", "none"];
+
+/// Every device once, at the first mount point it was found at.
+/// Bind mounts show the same device more than once, and virtual filesystems
+/// don't take up any disk space.
+fn physical_disks(disks: &Disks) -> Vec<&Disk> {
+    let mut devices = HashSet::new();
+    disks.iter()
+        .filter(|disk| !VIRTUAL_FILE_SYSTEMS.contains(&disk.file_system().to_string_lossy().as_ref()))
+        .filter(|disk| devices.insert(disk.name().to_os_string()))
+        .collect()
+}
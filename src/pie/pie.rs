@@ -0,0 +1,154 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+use anathema::component::{Children, Component, Context};
+use anathema::default_widgets::Canvas;
+use anathema::geometry::{LocalPos, Size};
+use anathema::state::Color;
+use anathema::widgets::Style;
+use crate::attributes::attributes::Attributes;
+
+const SLICE_COLOURS: [Color; 6] = [Color::Blue, Color::Red, Color::Green, Color::Yellow, Color::Magenta, Color::Cyan];
+/// Inner radius of a donut as a fraction of the outer radius
+const DONUT_HOLE: f32 = 0.5;
+
+/// Draws the proportions of a single series as slices of a pie.
+/// Every cell holds two pixels stacked on top of each other using half blocks,
+/// which makes the pixels roughly square so the pie stays round.
+#[derive(Default)]
+pub struct Pie {
+    values: Vec<f32>,
+    labels: Vec<String>,
+    donut: bool,
+}
+
+impl Pie {
+    pub(crate) fn configure(&mut self, attributes: &impl Attributes) {
+        self.values = attributes.numbers("data").unwrap_or_default();
+        self.labels = attributes.texts("labels").unwrap_or_default();
+        self.donut = attributes.flag("donut").unwrap_or(false);
+    }
+
+    fn clear_canvas(&self, canvas: &mut Canvas, size: Size) {
+        for y in 0..size.height {
+            for x in 0..size.width {
+                canvas.put(' ', Style::reset(), LocalPos::new(x, y));
+            }
+        }
+    }
+
+    /// The end of every slice as a fraction of the whole pie
+    pub(crate) fn slice_ends(&self) -> Vec<f32> {
+        let total: f32 = self.values.iter().filter(|value| **value > 0.0).sum();
+        if total <= 0.0 {
+            return vec![];
+        }
+
+        self.values.iter()
+            .scan(0.0, |end, value| {
+                *end += value.max(0.0) / total;
+                Some(*end)
+            })
+            .collect()
+    }
+
+    fn draw_pie(&self, canvas: &mut Canvas, radius: f32, donut: bool, slice_ends: &[f32]) {
+        let diameter = (radius * 2.0).ceil() as u16;
+        let inner = if donut { radius * DONUT_HOLE } else { 0.0 };
+
+        for y in 0..diameter.div_ceil(2) {
+            for x in 0..diameter {
+                let top = slice_at(x as f32 + 0.5, y as f32 * 2.0 + 0.5, radius, inner, slice_ends);
+                let bottom = slice_at(x as f32 + 0.5, y as f32 * 2.0 + 1.5, radius, inner, slice_ends);
+
+                let mut style = Style::new();
+                let character = match (top, bottom) {
+                    (Some(top), bottom) => {
+                        style.set_fg(slice_colour(top));
+                        if let Some(bottom) = bottom {
+                            style.set_bg(slice_colour(bottom));
+                        }
+                        '▀'
+                    }
+                    (None, Some(bottom)) => {
+                        style.set_fg(slice_colour(bottom));
+                        '▄'
+                    }
+                    (None, None) => continue,
+                };
+                canvas.put(character, style, LocalPos::new(x, y));
+            }
+        }
+    }
+
+    fn draw_legend(&self, canvas: &mut Canvas, size: Size, x: u16) {
+        let total: f32 = self.values.iter().filter(|value| **value > 0.0).sum();
+
+        for (index, value) in self.values.iter().enumerate().take(size.height as usize) {
+            let y = index as u16;
+            let mut style = Style::new();
+            style.set_fg(slice_colour(index));
+            put_str(canvas, size, "■", style, x, y);
+
+            let label = self.labels.get(index).map(String::as_str).unwrap_or("");
+            let percent = if total > 0.0 { value.max(0.0) / total * 100.0 } else { 0.0 };
+            put_str(canvas, size, &format!("{label} {percent:.0}%"), Style::reset(), x + 2, y);
+        }
+    }
+}
+
+/// Finds the slice a pixel belongs to, slices start at the top and run clockwise
+fn slice_at(x: f32, y: f32, radius: f32, inner: f32, slice_ends: &[f32]) -> Option<usize> {
+    let dx = x - radius;
+    let dy = y - radius;
+    let distance = (dx * dx + dy * dy).sqrt();
+    if distance > radius || distance < inner {
+        return None;
+    }
+
+    let angle = dx.atan2(-dy).rem_euclid(TAU) / TAU;
+    slice_ends.iter().position(|end| angle <= *end)
+        // Rounding can leave the last end a hair short of a full turn
+        .or_else(|| slice_ends.len().checked_sub(1))
+}
+
+fn slice_colour(index: usize) -> Color {
+    SLICE_COLOURS[index % SLICE_COLOURS.len()]
+}
+
+fn put_str(canvas: &mut Canvas, size: Size, text: &str, style: Style, x: u16, y: u16) {
+    for (offset, character) in text.chars().enumerate() {
+        let x = x + offset as u16;
+        if x >= size.width {
+            break;
+        }
+        canvas.put(character, style, LocalPos::new(x, y));
+    }
+}
+
+impl Component for Pie {
+    type State = ();
+    type Message = ();
+
+    fn on_tick(&mut self, _state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, _dt: Duration) {
+        self.configure(&context);
+        let legend_width = self.labels.iter().map(|label| label.chars().count()).max().unwrap_or(0) as u16 + 7;
+
+        children.elements().by_tag("canvas")
+            .first(|el, _| {
+                let size = el.size();
+                let canvas = el.to::<Canvas>();
+                self.clear_canvas(canvas, size);
+
+                // The pie is measured in pixels, which are one cell wide and half a cell tall
+                let pie_width = size.width.saturating_sub(legend_width + 1);
+                let radius = (pie_width as f32).min(size.height as f32 * 2.0) / 2.0;
+                if radius < 1.0 {
+                    return;
+                }
+
+                let slice_ends = self.slice_ends();
+                self.draw_pie(canvas, radius, self.donut, &slice_ends);
+                self.draw_legend(canvas, size, (radius * 2.0).ceil() as u16 + 1);
+            });
+    }
+}
@@ -0,0 +1,45 @@
+use rand::SeedableRng;
+use rand_pcg::Pcg64;
+
+/// An rng that can be seeded from a `seed` attribute.
+/// `Pcg64` is used rather than `StdRng` as its output is fixed across rand
+/// versions and platforms, which the golden files rely on.
+/// Without a seed it is seeded from the OS, and it is only reseeded when the
+/// seed changes so reading the attribute every tick does not repeat the output.
+pub struct SeededRng {
+    seed: Option<u64>,
+    rng: Pcg64,
+}
+
+impl Default for SeededRng {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            rng: Pcg64::from_os_rng(),
+        }
+    }
+}
+
+impl SeededRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            rng: Pcg64::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns true if the rng was reseeded
+    pub fn reseed(&mut self, seed: Option<u64>) -> bool {
+        match seed {
+            Some(seed) if self.seed != Some(seed) => {
+                *self = Self::from_seed(seed);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn rng(&mut self) -> &mut Pcg64 {
+        &mut self.rng
+    }
+}
@@ -1,26 +1,99 @@
+use std::collections::VecDeque;
 use anathema::component::{Children, Component, Context};
+use anathema::resolver::ValueKind;
 use anathema::state::{List, State, Value};
 
+/// Number of samples kept per core for the heatmap
+const HISTORY_LENGTH: usize = 120;
+
 #[derive(Default)]
-pub struct Cpus {}
+pub struct Cpus {
+    history: VecDeque<Vec<f32>>,
+}
 
 
 #[derive(Default, State)]
 pub struct CpusState {
     pub cpu_count: Value<u8>,
     pub cpu_usage: Value<List<f32>>,
+    pub total_usage: Value<f32>,
+    pub memory_usage: Value<f32>,
+    pub disk_usage: Value<f32>,
+    pub memory_used: Value<f32>,
+    pub memory_cache: Value<f32>,
+    pub memory_free: Value<f32>,
+    pub disk_mounts: Value<List<String>>,
+    pub disk_mount_usage: Value<List<f32>>,
 }
 
 pub struct CpusMessage {
     pub cpu_usage: Vec<f32>,
+    pub total_usage: f32,
+    /// Used and total memory in bytes
+    pub memory: (u64, u64),
+    /// Memory that is not used by processes but can be reclaimed, in bytes
+    pub memory_cache: u64,
+    pub memory_free: u64,
+    /// Used and total disk space in bytes, across all disks
+    pub disk: (u64, u64),
+    /// Used space in bytes for every mount point
+    pub disk_mounts: Vec<(String, u64)>,
+}
+
+impl Cpus {
+    pub(crate) fn record(&mut self, cpu_usage: &[f32]) {
+        // Start over if the number of cores changes
+        if self.history.back().is_some_and(|sample| sample.len() != cpu_usage.len()) {
+            self.history.clear();
+        }
+
+        self.history.push_back(cpu_usage.to_vec());
+        if self.history.len() > HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+    }
+
+    /// One row per core, one column per sample
+    pub(crate) fn history_rows(&self, cpu_count: usize) -> ValueKind {
+        ValueKind::List((0..cpu_count).map(|core| {
+            ValueKind::List(self.history.iter().map(|sample| ValueKind::Float(sample[core] as f64)).collect())
+        }).collect())
+    }
 }
 
 impl Component for Cpus {
     type State = CpusState;
     type Message = CpusMessage;
 
-    fn on_message(&mut self, message: Self::Message, state: &mut Self::State, _children: Children<'_, '_>, _context: Context<'_, '_, Self::State>) {
+    fn on_message(&mut self, message: Self::Message, state: &mut Self::State, mut children: Children<'_, '_>, _context: Context<'_, '_, Self::State>) {
+        self.record(&message.cpu_usage);
+
+        children.components().by_name("heatmap")
+            .first(|_, _, attrs| {
+                attrs.set("data", self.history_rows(message.cpu_usage.len()));
+            });
+
+        state.total_usage.set(message.total_usage);
+        state.memory_usage.set(percentage(message.memory));
+        state.disk_usage.set(percentage(message.disk));
+        state.memory_used.set(gigabytes(message.memory.0));
+        state.memory_cache.set(gigabytes(message.memory_cache));
+        state.memory_free.set(gigabytes(message.memory_free));
+        state.disk_mounts.set(List::from_iter(message.disk_mounts.iter().map(|(mount, _)| mount.clone())));
+        state.disk_mount_usage.set(List::from_iter(message.disk_mounts.iter().map(|(_, used)| gigabytes(*used))));
         state.cpu_count.set(message.cpu_usage.len() as u8);
         state.cpu_usage.set(List::from_iter(message.cpu_usage));
     }
-}
\ No newline at end of file
+}
+
+fn percentage((used, total): (u64, u64)) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f32 / total as f32 * 100.0
+    }
+}
+
+fn gigabytes(bytes: u64) -> f32 {
+    bytes as f32 / 1_073_741_824.0
+}
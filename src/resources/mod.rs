@@ -1 +1 @@
-pub(crate) mod cpus;
\ No newline at end of file
+pub mod cpus;
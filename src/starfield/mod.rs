@@ -1 +1,2 @@
-pub(crate) mod starfield;
\ No newline at end of file
+pub mod parallax;
+pub mod starfield;
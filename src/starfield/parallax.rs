@@ -0,0 +1,118 @@
+use std::time::Duration;
+use anathema::geometry::LocalPos;
+use anathema::widgets::Style;
+use rand::Rng;
+use rand::prelude::Distribution;
+use crate::colour::ramp::{ColourMode, ColourRamp};
+use crate::starfield::starfield::GLYPHS;
+use crate::surface::surface::Surface;
+
+/// The parallax layers, from the furthest to the nearest
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StarSize {
+    Small,
+    Medium,
+    Large,
+    ExtraLarge,
+}
+
+impl Distribution<StarSize> for rand::distr::StandardUniform {
+    /// Far away stars are the most common
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> StarSize {
+        match rng.random_range(0..10) {
+            0..4 => StarSize::Small,
+            4..7 => StarSize::Medium,
+            7..9 => StarSize::Large,
+            _ => StarSize::ExtraLarge,
+        }
+    }
+}
+
+impl From<StarSize> for usize {
+    fn from(size: StarSize) -> Self {
+        match size {
+            StarSize::Small => 0,
+            StarSize::Medium => 1,
+            StarSize::Large => 2,
+            StarSize::ExtraLarge => 3,
+        }
+    }
+}
+
+impl StarSize {
+    /// Cells per second when the field speed is 1
+    fn speed(&self) -> f32 {
+        match self {
+            StarSize::Small => 2.0,
+            StarSize::Medium => 5.0,
+            StarSize::Large => 10.0,
+            StarSize::ExtraLarge => 20.0,
+        }
+    }
+
+    fn brightness(&self) -> f32 {
+        usize::from(*self) as f32 / 3.0
+    }
+
+    fn glyph(&self) -> char {
+        GLYPHS[usize::from(*self)]
+    }
+}
+
+/// A star scrolling sideways at the speed of its layer
+pub(crate) struct ParallaxStar {
+    x: f32,
+    y: f32,
+    layer: StarSize,
+    /// The cell the star was last drawn in
+    drawn: Option<(u16, u16)>,
+}
+
+impl ParallaxStar {
+    pub(crate) fn new(rng: &mut impl Rng, width: i32, height: i32) -> Self {
+        Self {
+            x: rng.random_range(0.0..width.max(1) as f32),
+            y: rng.random_range(0..height.max(1)) as f32,
+            layer: rng.random(),
+            drawn: None,
+        }
+    }
+
+    /// Scrolls from right to left, stars leaving one side come back on the
+    /// other on a new row and layer
+    pub(crate) fn update(&mut self, rng: &mut impl Rng, dt: Duration, field_speed: f32, width: i32, height: i32) {
+        let width = width.max(1) as f32;
+        self.x -= self.layer.speed() * field_speed * dt.as_secs_f32();
+
+        if self.x < 0.0 || self.x >= width {
+            self.x = self.x.rem_euclid(width);
+            self.y = rng.random_range(0..height.max(1)) as f32;
+            self.layer = rng.random();
+        }
+    }
+
+    /// Keeps the star at the same place relative to the canvas
+    pub(crate) fn rescale(&mut self, from: (i32, i32), to: (i32, i32)) {
+        self.x = self.x * to.0 as f32 / from.0.max(1) as f32;
+        self.y = (self.y * to.1 as f32 / from.1.max(1) as f32).floor();
+        self.drawn = self.drawn.filter(|&(x, y)| (x as i32) < to.0 && (y as i32) < to.1);
+    }
+
+    pub(crate) fn erase(&mut self, canvas: &mut impl Surface) {
+        if let Some((x, y)) = self.drawn.take() {
+            canvas.put(' ', Style::new(), LocalPos { x, y });
+        }
+    }
+
+    pub(crate) fn draw(&mut self, canvas: &mut impl Surface, width: i32, height: i32, palette: &ColourRamp, mode: ColourMode) {
+        let (x, y) = (self.x as i32, self.y as i32);
+        if x < 0 || y < 0 || x >= width || y >= height {
+            return;
+        }
+
+        let mut style = Style::new();
+        style.set_fg(palette.colour(self.layer.brightness(), mode));
+        canvas.put(self.layer.glyph(), style, LocalPos { x: x as u16, y: y as u16 });
+        self.drawn = Some((x as u16, y as u16));
+    }
+}
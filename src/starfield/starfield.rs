@@ -1,257 +1,387 @@
 use std::time::Duration;
-use anathema::component::{Children, Component, Context};
+use anathema::component::{Children, Component, Context, KeyCode, KeyEvent};
 use anathema::default_widgets::Canvas;
 use anathema::geometry::LocalPos;
 use anathema::state::{State, Value};
 use anathema::widgets::Style;
-use rand::prelude::Distribution;
+use rand::Rng;
+use crate::attributes::attributes::Attributes;
+use crate::colour::ramp::{ColourMode, ColourRamp};
+use crate::random::seed::SeededRng;
+use crate::starfield::parallax::ParallaxStar;
+use crate::surface::surface::Surface;
+
+/// How far away new stars appear
+const MAX_DEPTH: f32 = 32.0;
+/// Stars closer than this have flown past the viewer
+const MIN_DEPTH: f32 = 0.1;
+/// Terminal cells are about twice as tall as they are wide
+const CELL_ASPECT: f32 = 0.5;
+/// From the furthest stars to the nearest
+pub(crate) const GLYPHS: [char; 4] = ['.', '·', '*', '✦'];
+/// How far a single key press steers
+const STEER_STEP: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StarfieldMode {
+    /// Flying into the field
+    #[default]
+    Warp,
+    /// Layers of stars scrolling sideways
+    Parallax,
+}
+
+impl From<&str> for StarfieldMode {
+    fn from(value: &str) -> Self {
+        match value {
+            "parallax" => StarfieldMode::Parallax,
+            _ => StarfieldMode::Warp,
+        }
+    }
+}
 
-#[derive(Default)]
 pub struct Starfield {
     stars: Vec<Star>,
+    parallax: Vec<ParallaxStar>,
+    rng: SeededRng,
+    pub mode: StarfieldMode,
+    /// Scales how fast the whole field moves
+    pub speed: f32,
+    /// Horizontal field of view in degrees
+    pub fov: f32,
+    /// Colours the stars from far to near
+    pub palette: ColourRamp,
+    pub colour_mode: ColourMode,
+    /// Length of the streak behind every star, in seconds of movement. 0 turns them off
+    pub trails: f32,
+    /// Where the vanishing point sits, as a fraction of the canvas
+    pub focus: (f32, f32),
+    /// Sideways and vertical drift for every unit flown forward
+    pub heading: (f32, f32),
+    /// Steering added on top of the heading with the arrow keys
+    steering: (f32, f32),
+    /// How many stars are on the canvas
+    pub no_of_stars: u16,
+    /// Size of the canvas the stars were last laid out for
+    canvas_size: Option<(i32, i32)>,
+}
+
+impl Default for Starfield {
+    fn default() -> Self {
+        Self {
+            stars: vec![],
+            parallax: vec![],
+            rng: SeededRng::default(),
+            mode: StarfieldMode::default(),
+            speed: 1.0,
+            fov: 90.0,
+            palette: ColourRamp::starlight(),
+            colour_mode: ColourMode::default(),
+            trails: 0.0,
+            focus: (0.5, 0.5),
+            heading: (0.0, 0.0),
+            steering: (0.0, 0.0),
+            no_of_stars: 50,
+            canvas_size: None,
+        }
+    }
 }
 
 impl Starfield {
-    pub(crate) fn initialise_stars(&mut self, width: i32, height: i32, no_of_stars: u16) {
-        self.stars = (0..no_of_stars).map(|_| {
-            let mut star= Star::new(width, height);
-            star.counter_x = width / 2;
-            star.counter_y = height / 2;
-            star
-        }).collect();
+    /// Reads the attributes, returns true if a new seed was set
+    pub(crate) fn configure(&mut self, attributes: &impl Attributes) -> bool {
+        // A new seed starts the field over so the frames are reproducible
+        let reseeded = self.rng.reseed(attributes.integer("seed").map(|seed| seed as u64));
+        self.speed = attributes.number("speed").unwrap_or(1.0);
+        self.fov = attributes.number("fov").unwrap_or(90.0);
+        self.palette = attributes.text("palette")
+            .map(ColourRamp::from)
+            .unwrap_or_else(ColourRamp::starlight);
+        self.colour_mode = ColourMode::from_attribute(attributes.text("colours"));
+        self.trails = attributes.number("trails").unwrap_or(0.0);
+        self.mode = attributes.text("mode")
+            .unwrap_or("warp").into();
+        self.focus = (
+            attributes.number("focus_x").unwrap_or(0.5),
+            attributes.number("focus_y").unwrap_or(0.5),
+        );
+        self.heading = (
+            attributes.number("heading_x").unwrap_or(0.0),
+            attributes.number("heading_y").unwrap_or(0.0),
+        );
+        self.no_of_stars = attributes.integer("stars").unwrap_or(50) as u16;
+        reseeded
     }
 
-    pub(crate) fn update_stars(&mut self, state: &mut StarfieldState, canvas: &mut Canvas, dt: Duration, width: i32, height: i32) {
+    /// Creates the stars the first time the canvas size is known, and again when
+    /// the seed, the mode or the number of stars changes.
+    /// Warp stars are projected from the canvas size on every update, so after a
+    /// resize only the cells drawn on the old canvas have to be dropped.
+    /// Parallax stars are moved to the same place on the new canvas.
+    pub(crate) fn fit(&mut self, width: i32, height: i32, reseeded: bool) {
+        let no_of_stars = self.no_of_stars;
+        let count = match self.mode {
+            StarfieldMode::Warp => self.stars.len(),
+            StarfieldMode::Parallax => self.parallax.len(),
+        };
+
+        if reseeded || count != no_of_stars as usize {
+            self.initialise_stars(width, height, no_of_stars);
+        } else if let Some(previous) = self.canvas_size.filter(|&size| size != (width, height)) {
+            for star in self.stars.iter_mut() {
+                star.drawn.retain(|&(x, y)| (x as i32) < width && (y as i32) < height);
+            }
+            for star in self.parallax.iter_mut() {
+                star.rescale(previous, (width, height));
+            }
+        }
+        self.canvas_size = Some((width, height));
+    }
+
+    fn initialise_stars(&mut self, width: i32, height: i32, no_of_stars: u16) {
+        let rng = self.rng.rng();
+        if self.mode == StarfieldMode::Parallax {
+            self.parallax = (0..no_of_stars)
+                .map(|_| ParallaxStar::new(rng, width, height))
+                .collect();
+            return;
+        }
+
+
+        // Spread the first stars over every depth so the field starts out full
+        self.stars = (0..no_of_stars)
+            .map(|_| {
+                let depth = rng.random_range(MIN_DEPTH..MAX_DEPTH);
+                Star::new(rng, depth)
+            })
+            .collect();
+
+        let projection = Projection::new(self.fov, self.focus, width, height);
+        for star in self.stars.iter_mut() {
+            star.project(&projection);
+        }
+    }
+
+    /// Moves the stars by the time that has passed and redraws them
+    pub(crate) fn update_stars(&mut self, state: &mut StarfieldState, canvas: &mut impl Surface, dt: Duration, width: i32, height: i32) {
         for star in self.stars.iter_mut() {
-            star.draw(canvas, width, height, ' '); // Erase the star by drawing a space
+            star.erase(canvas);
+        }
+        for star in self.parallax.iter_mut() {
+            star.erase(canvas);
         }
 
+        // Stars left over from the other mode are gone once they are erased
+        if self.mode == StarfieldMode::Parallax {
+            self.stars.clear();
+            let rng = self.rng.rng();
+            for star in self.parallax.iter_mut() {
+                star.update(rng, dt, self.speed, width, height);
+                star.draw(canvas, width, height, &self.palette, self.colour_mode);
+            }
+            return;
+        }
+        self.parallax.clear();
+
+        let projection = Projection::new(self.fov, self.focus, width, height);
+        let velocity = (
+            (self.heading.0 + self.steering.0) * self.speed,
+            (self.heading.1 + self.steering.1) * self.speed,
+            self.speed,
+        );
+        let rng = self.rng.rng();
         for star in self.stars.as_mut_slice() {
-            star.update(state, dt, width, height);
-            star.draw(canvas, width, height, '*');
+            star.update(rng, state, dt, velocity, &projection);
+
+            if self.trails > 0.0 {
+                star.draw_trail(canvas, &projection, velocity, self.trails, &self.palette, self.colour_mode);
+            }
+
+            let mut style = Style::new();
+            style.set_fg(self.palette.colour(star.brightness, self.colour_mode));
+            star.draw(canvas, width, height, glyph(star.brightness), style);
         }
     }
+
+    /// Banks with the arrow keys, `0` flies straight ahead again
+    fn steer(&mut self, code: KeyCode) {
+        let (x, y) = match code {
+            KeyCode::Left => (-STEER_STEP, 0.0),
+            KeyCode::Right => (STEER_STEP, 0.0),
+            KeyCode::Up => (0.0, -STEER_STEP),
+            KeyCode::Down => (0.0, STEER_STEP),
+            KeyCode::Char('0') => {
+                self.steering = (0.0, 0.0);
+                return;
+            }
+            _ => return,
+        };
+        self.steering.0 = (self.steering.0 + x).clamp(-1.0, 1.0);
+        self.steering.1 = (self.steering.1 + y).clamp(-1.0, 1.0);
+    }
 }
 
 #[derive(State, Default)]
 pub struct StarfieldState {
-    pub x_start: Value<i32>,
-    pub y_start: Value<i32>,
-    pub x_pos: Value<f32>,
-    pub y_pos: Value<f32>,
-    pub x_ratio: Value<f32>,
-    pub y_ratio: Value<f32>,
-    pub counter_x: Value<i32>,
-    pub counter_y: Value<i32>,
-    pub radius: Value<f32>,
+    pub x_pos: Value<i32>,
+    pub y_pos: Value<i32>,
+    pub depth: Value<f32>,
 }
 
-struct Star {
-    x_start: i32,
-    y_start: i32,
-    pos_x: f32,
-    pos_y: f32,
-    radius: StarSize,
-        max_radius: StarSize,
-        speed: i32,
-        counter_x: i32,
-        counter_y: i32,
+/// Projects points in front of the viewer onto the canvas
+struct Projection {
+    /// Distance from the eye to the screen, in cells
+    focal_length: f32,
+    /// The vanishing point
+    center_x: f32,
+    center_y: f32,
+    width: i32,
+    height: i32,
+}
+
+impl Projection {
+    fn new(fov: f32, focus: (f32, f32), width: i32, height: i32) -> Self {
+        let half_angle = (fov.clamp(10.0, 170.0) / 2.0).to_radians();
+        Self {
+            focal_length: width as f32 / 2.0 / half_angle.tan(),
+            center_x: width as f32 * focus.0.clamp(0.0, 1.0),
+            center_y: height as f32 * focus.1.clamp(0.0, 1.0),
+            width,
+            height,
+        }
     }
 
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-    enum StarSize {
-        Small,
-        Medium,
-        Large,
-        ExtraLarge,
+    fn contains(&self, (x, y): (i32, i32)) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
     }
 
-    impl Distribution<StarSize> for rand::distr::StandardUniform {
-        fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> StarSize {
-            match rng.random_range(0..4) {
-                0 => StarSize::Small,
-                1 => StarSize::Medium,
-                2 => StarSize::Large,
-                3 => StarSize::ExtraLarge,
-                _ => unreachable!(),
-            }
-        }
+    fn project(&self, x: f32, y: f32, z: f32) -> (i32, i32) {
+        let screen_x = self.center_x + x / z * self.focal_length;
+        let screen_y = self.center_y + y / z * self.focal_length * CELL_ASPECT;
+        (screen_x.floor() as i32, screen_y.floor() as i32)
     }
+}
 
-    impl From<StarSize> for i32 {
-        fn from(size: StarSize) -> Self {
-            match size {
-                StarSize::Small => 0,
-                StarSize::Medium => 1,
-                StarSize::Large => 2,
-                StarSize::ExtraLarge => 3,
-            }
+struct Star {
+    x: f32,
+    y: f32,
+    z: f32,
+    /// Depth per second
+    speed: f32,
+    /// Where the star was last drawn
+    screen: (i32, i32),
+    radius: i32,
+    brightness: f32,
+    /// Every cell drawn last frame, so they can be erased again
+    drawn: Vec<(u16, u16)>,
+}
+
+fn glyph(brightness: f32) -> char {
+    let index = (brightness * GLYPHS.len() as f32) as usize;
+    GLYPHS[index.min(GLYPHS.len() - 1)]
+}
+
+impl Star {
+    fn new(rng: &mut impl Rng, z: f32) -> Self {
+        Star {
+            x: rng.random_range(-MAX_DEPTH..MAX_DEPTH),
+            y: rng.random_range(-MAX_DEPTH..MAX_DEPTH),
+            z,
+            speed: rng.random_range(6.0..10.0),
+            screen: (0, 0),
+            radius: 0,
+            brightness: 0.0,
+            drawn: vec![],
         }
     }
 
-    impl From<i32> for StarSize {
-        fn from(value: i32) -> Self {
-            match value {
-                0 => StarSize::Small,
-                1 => StarSize::Medium,
-                2 => StarSize::Large,
-                3 => StarSize::ExtraLarge,
-                _ => unreachable!("Invalid star size value: {}", value),
-            }
+    /// Flies the star towards the viewer, `velocity` is the direction of flight
+    /// scaled by the speed of the whole field
+    fn update(&mut self, rng: &mut impl Rng, state: &mut StarfieldState, dt: Duration, velocity: (f32, f32, f32), projection: &Projection) {
+        let distance = self.speed * dt.as_secs_f32();
+        self.x -= velocity.0 * distance;
+        self.y -= velocity.1 * distance;
+        self.z -= velocity.2 * distance;
+        if self.z < MIN_DEPTH {
+            *self = Star::new(rng, MAX_DEPTH);
         }
-    }
 
-    impl Star {
-        pub fn new(width: i32, height: i32) -> Self {
-            let center_x = width / 2;
-            let center_y = height / 2;
-            let x_start = Self::get_random(-center_x, center_x);
-            let y_start = Self::get_random(-center_y, center_y);
-
-            Star {
-                x_start,
-                y_start,
-                pos_x: x_start as f32,
-                pos_y: y_start as f32,
-                radius: StarSize::Small,
-                max_radius: rand::random(),
-            speed: rand::random_range(1..3),
-            counter_x: rand::random_range(0..width / 2),
-            counter_y: rand::random_range(0..height / 2),
+        self.project(projection);
+
+        // Stars that left the screen start over in the distance
+        if !projection.contains(self.screen) {
+            *self = Star::new(rng, MAX_DEPTH);
+            self.project(projection);
         }
+
+        state.x_pos.set(self.screen.0);
+        state.y_pos.set(self.screen.1);
+        state.depth.set(self.z);
     }
 
-    fn get_random(start: i32, end: i32) -> i32 {
-        rand::random_range(start..end)
+    fn project(&mut self, projection: &Projection) {
+        self.screen = projection.project(self.x, self.y, self.z);
+
+        // Stars grow and brighten as they get closer
+        let nearness = 1.0 - (self.z / MAX_DEPTH).clamp(0.0, 1.0);
+        self.brightness = nearness;
+        // Only the nearest stars are wider than a single glyph
+        self.radius = (nearness * nearness * 1.5) as i32;
     }
 
-    fn update(&mut self, state: &mut StarfieldState, _dt: Duration, width: i32, height: i32) {
-        self.counter_x -= self.speed; // as u16 * dt.as_secs() as u16;
-        self.counter_y -= self.speed; // as u16 * dt.as_secs() as u16;
-        state.counter_x.set(self.counter_x);
-        state.counter_y.set(self.counter_y);
-        
-        if self.counter_x < 1 || self.counter_y < 1 {
-            *self = Star::new(width, height);
+    fn erase(&mut self, canvas: &mut impl Surface) {
+        for (x, y) in self.drawn.drain(..) {
+            canvas.put(' ', Style::new(), LocalPos { x, y });
         }
-        
-        state.x_start.set(self.x_start);
-        state.y_start.set(self.y_start);
-
-        let x_ratio = self.x_start as f32 / self.counter_x as f32;
-        let y_ratio = self.y_start as f32 / self.counter_y as f32;
-        state.x_ratio.set(x_ratio);
-        state.y_ratio.set(y_ratio);
-
-
-        let star_x = Self::remap(x_ratio, 0.0, 1.0, 0.0, width as f32 / 2.0);
-        let star_y = Self::remap(y_ratio, 0.0, 1.0, 0.0, height as f32 / 2.0);
-        self.pos_x = star_x;
-        self.pos_y = star_y;
-        state.x_pos.set(self.pos_x);
-        state.y_pos.set(self.pos_y);
-
-        let _max_radius: i32 = self.max_radius.into();
-        let x = Self::remap(self.counter_x as f32, 0.0, width as f32 / 2.0, 0.0, 1.0);
-        let y = Self::remap(self.counter_y as f32, 0.0, height as f32 / 2.0, 0.0, 1.0);
-        let y = if x < y {
-            x
-        } else {
-            y
-        };
-        
-        state.radius.set(x);
-        match y {
-            0.0..0.25 => {
-                self.radius = StarSize::ExtraLarge;
-            },
-            0.25..0.50 => {
-                self.radius = StarSize::Large;
-            },
-            0.50..0.75 => {
-                self.radius = StarSize::Medium;
-            },
-            _ => {
-                self.radius = StarSize::Small;
-            },
-        };
+    }
 
-        // check if the star is out of bounds
-        if self.pos_x < (-width / 2) as f32 || self.pos_x > (width / 2) as f32 ||
-           self.pos_y < ((-height) / 2) as f32 || self.pos_y > (height / 2) as f32 {
-            *self = Star::new(width, height);
+    /// A streak from where the star was `seconds` ago towards where it is, fading
+    /// out at the tail. The tail never reaches further back than where the star appeared.
+    fn draw_trail(&mut self, canvas: &mut impl Surface, projection: &Projection, velocity: (f32, f32, f32), seconds: f32, palette: &ColourRamp, mode: ColourMode) {
+        let mut distance = self.speed * seconds;
+        if velocity.2 > 0.0 {
+            distance = distance.min((MAX_DEPTH - self.z) / velocity.2);
         }
-    }
+        let tail = projection.project(
+            self.x + velocity.0 * distance,
+            self.y + velocity.1 * distance,
+            (self.z + velocity.2 * distance).max(MIN_DEPTH),
+        );
+
+        let (head_x, head_y) = (self.screen.0 as f32, self.screen.1 as f32);
+        let (tail_x, tail_y) = (tail.0 as f32, tail.1 as f32);
 
-    fn remap(value: f32, i_start: f32, i_stop: f32, o_start: f32, o_stop: f32) -> f32 {
-        o_start + (o_stop - o_start) * ((value - i_start) / (i_stop - i_start))
+        let steps = (head_x - tail_x).abs().max((head_y - tail_y).abs()).round() as i32;
+        for step in 0..steps {
+            let t = step as f32 / steps as f32;
+            let x = (tail_x + (head_x - tail_x) * t).round() as i32;
+            let y = (tail_y + (head_y - tail_y) * t).round() as i32;
+
+            let brightness = self.brightness * t;
+            let mut style = Style::new();
+            style.set_fg(palette.colour(brightness, mode));
+            self.put(canvas, projection.width, projection.height, glyph(brightness), style, (x, y));
+        }
     }
 
-    fn draw(&self, canvas: &mut Canvas, width: i32, height: i32, character: char) {
-        // Draw the star on the canvas
-        let x = (self.pos_x + (width / 2) as f32) as u16;
-        let y = (self.pos_y + (height / 2) as f32) as u16;
-
-        match self.radius {
-            StarSize::Small => {
-                let style = Style::reset();
-                // let style = Style {
-                //     fg: Some(Color::Red),
-                //     bg: None,
-                //     attributes: Attributes::empty(),
-                // };
-                canvas.put(character, style, LocalPos { x, y, });
-            },
-            StarSize::Medium => {
-                let style = Style::reset();
-                // let style = Style {
-                //     fg: Some(Color::Green),
-                //     bg: None,
-                //     attributes: Attributes::empty(),
-                // };
-                
-                canvas.put(character, style, LocalPos { x, y, });
-                if x + 1 < width as u16 {
-                    canvas.put(character, style, LocalPos { x: x + 1, y, });
+    fn draw(&mut self, canvas: &mut impl Surface, width: i32, height: i32, character: char, style: Style) {
+        // A disc twice as wide as it is tall, so it looks round in the terminal
+        let (center_x, center_y) = self.screen;
+        for dy in -self.radius..=self.radius {
+            for dx in -self.radius * 2..=self.radius * 2 {
+                if (dx * dx) / 4 + dy * dy > self.radius * self.radius {
+                    continue;
                 }
-            },
-            StarSize::Large => {
-                let style = Style::reset();
-                // let style = Style {
-                //     fg: Some(Color::Blue),
-                //     bg: None,
-                //     attributes: Attributes::empty(),
-                // };
-                
-                canvas.put(character, style, LocalPos { x, y, });
-                if x + 1 < width as u16 {
-                    canvas.put(character, style, LocalPos { x: x + 1, y, });
-                }
-                if x > 0 {
-                    canvas.put(character, style, LocalPos { x: x - 1, y, });
-                }
-            },
-            StarSize::ExtraLarge => {
-                let style = Style::reset();
-                // let style = Style {
-                //     fg: Some(Color::White),
-                //     bg: None,
-                //     attributes: Attributes::empty(),
-                // };
-                canvas.put(character, style, LocalPos { x, y, });
-                if x + 1 < width as u16 {
-                    canvas.put(character, style, LocalPos { x: x + 1, y, });
-                }
-                if x != 0 {
-                    canvas.put(character, style, LocalPos { x: x - 1, y, });
-                }
-                if y + 1 < height as u16 {
-                    canvas.put(character, style, LocalPos { x, y: y + 1, });
-                }
-                if y > 0 {
-                    canvas.put(character, style, LocalPos { x, y: y - 1, });
-                }
-            },
+
+                self.put(canvas, width, height, character, style, (center_x + dx, center_y + dy));
+            }
+        }
+    }
+
+    fn put(&mut self, canvas: &mut impl Surface, width: i32, height: i32, character: char, style: Style, (x, y): (i32, i32)) {
+        if x >= 0 && y >= 0 && x < width && y < height {
+            canvas.put(character, style, LocalPos { x: x as u16, y: y as u16 });
+            self.drawn.push((x as u16, y as u16));
         }
     }
 }
@@ -260,24 +390,25 @@ impl Component for Starfield {
     type State = StarfieldState;
     type Message = ();
 
-    fn on_mount(&mut self, _state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>) {
-        // TODO: Why is this not working?
+    fn on_tick(&mut self, state: &mut Self::State, mut children: Children<'_, '_>, context: Context<'_, '_, Self::State>, dt: Duration) {
+        let reseeded = self.configure(&context);
+
         children.elements().by_tag("canvas")
             .first(|el, _| {
+                // The canvas has no size until it has been laid out
                 let size = el.size();
-                let no_of_stars = context.attributes.get_as::<u16>("stars")
-                    .unwrap_or(50);
+                if size.width == 0 || size.height == 0 {
+                    return;
+                }
 
-                self.initialise_stars(size.width as i32, size.height as i32, no_of_stars);
-            });
-    }
+                self.fit(size.width as i32, size.height as i32, reseeded);
 
-    fn on_tick(&mut self, state: &mut Self::State, mut children: Children<'_, '_>, _context: Context<'_, '_, Self::State>, dt: Duration) {
-        children.elements().by_tag("canvas")
-            .first(|el, _| {
-                let size = el.size();
                 let canvas = el.to::<Canvas>();
                 self.update_stars(state, canvas, dt, size.width as i32, size.height as i32);
             });
     }
+
+    fn on_key(&mut self, key: KeyEvent, _state: &mut Self::State, _children: Children<'_, '_>, _context: Context<'_, '_, Self::State>) {
+        self.steer(key.code);
+    }
 }
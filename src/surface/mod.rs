@@ -0,0 +1,2 @@
+pub mod surface;
+pub mod text_grid;
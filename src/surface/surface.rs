@@ -0,0 +1,16 @@
+use anathema::default_widgets::Canvas;
+use anathema::geometry::LocalPos;
+use anathema::widgets::Style;
+
+/// Something widgets can draw characters onto.
+/// This is the canvas when running, and a plain grid of characters when a
+/// widget is rendered for export or in tests.
+pub trait Surface {
+    fn put(&mut self, character: char, style: Style, pos: LocalPos);
+}
+
+impl Surface for Canvas {
+    fn put(&mut self, character: char, style: Style, pos: LocalPos) {
+        Canvas::put(self, character, style, pos);
+    }
+}
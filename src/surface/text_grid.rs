@@ -0,0 +1,53 @@
+use std::fmt::{Display, Formatter};
+use anathema::geometry::{LocalPos, Size};
+use anathema::widgets::Style;
+use crate::surface::surface::Surface;
+
+/// A surface that only keeps the characters, anything drawn outside of it is ignored.
+/// Spaces drawn with a background colour, like the bars of a bar graph, are
+/// kept as full blocks so they still show up.
+pub struct TextGrid {
+    width: u16,
+    height: u16,
+    cells: Vec<char>,
+}
+
+impl TextGrid {
+    pub fn new(size: Size) -> Self {
+        Self {
+            width: size.width,
+            height: size.height,
+            cells: vec![' '; size.width as usize * size.height as usize],
+        }
+    }
+
+    /// Every row of the grid, without trailing spaces
+    pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.cells
+            .chunks(self.width.max(1) as usize)
+            .take(self.height as usize)
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+    }
+}
+
+impl Surface for TextGrid {
+    fn put(&mut self, character: char, style: Style, pos: LocalPos) {
+        let character = match character {
+            ' ' if style.bg.is_some() => '█',
+            _ => character,
+        };
+
+        if pos.x < self.width && pos.y < self.height {
+            self.cells[pos.y as usize * self.width as usize + pos.x as usize] = character;
+        }
+    }
+}
+
+impl Display for TextGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::gauge::gauge::Gauge;
+use crate::histogram::binning::Binning;
+use crate::histogram::histogram::Histogram;
+use crate::pie::pie::Pie;
+use crate::testing::harness::attributes;
+
+#[test]
+fn gauge_fills_by_value_over_max() {
+    let mut gauge = Gauge::default();
+    gauge.configure(&attributes(&[("value", "50"), ("max", "200")]));
+
+    assert_eq!(gauge.fraction(), 0.25);
+}
+
+#[test]
+fn histogram_reads_the_binning() {
+    assert_eq!(Histogram::read_binning(&attributes(&[])), Binning::Count(10));
+    assert_eq!(Histogram::read_binning(&attributes(&[("bins", "5")])), Binning::Count(5));
+    assert_eq!(Histogram::read_binning(&attributes(&[("binning", "width"), ("bin_width", "2")])), Binning::Width(2.0));
+    assert_eq!(Histogram::read_binning(&attributes(&[("binning", "fd")])), Binning::FreedmanDiaconis);
+}
+
+#[test]
+fn pie_slices_follow_the_data() {
+    let mut pie = Pie::default();
+    pie.configure(&attributes(&[("data", "1, 3"), ("labels", "used, free")]));
+
+    assert_eq!(pie.slice_ends(), vec![0.25, 1.0]);
+}
@@ -0,0 +1,35 @@
+use crate::histogram::binning::{Binning, Bins};
+
+#[test]
+fn freedman_diaconis_picks_the_width_from_the_spread() {
+    let samples: Vec<f32> = (0..100).map(|sample| sample as f32).collect();
+    let bins = Binning::FreedmanDiaconis.bin(&samples);
+
+    // An interquartile range of 49.5 over 100 samples gives bins about 21 wide
+    assert_eq!(bins.counts.len(), 5);
+    assert_eq!(bins.counts.iter().sum::<usize>(), 100);
+}
+
+#[test]
+fn samples_without_a_span_share_a_single_bin() {
+    let bins = Binning::Count(10).bin(&[3.0, 3.0, 3.0]);
+
+    assert_eq!(bins, Bins { edges: vec![3.0, 4.0], counts: vec![3] });
+}
+
+#[test]
+fn narrow_bin_widths_are_capped() {
+    let bins = Binning::Width(0.001).bin(&[0.0, 10.0]);
+
+    assert_eq!(bins.counts.len(), 200);
+    assert_eq!(bins.edges.len(), 201);
+    assert_eq!(bins.counts.iter().sum::<usize>(), 2);
+}
+
+#[test]
+fn samples_that_are_not_finite_are_left_out() {
+    let bins = Binning::Count(2).bin(&[f32::NAN, 1.0, 2.0, f32::INFINITY]);
+    assert_eq!(bins.counts, vec![1, 1]);
+
+    assert_eq!(Binning::Count(2).bin(&[f32::NAN]), Bins::default());
+}
@@ -0,0 +1,46 @@
+use crate::graph::downsample::Aggregate;
+
+#[test]
+fn points_that_fit_are_left_alone() {
+    let points = [1.0, 2.0, 3.0];
+
+    assert_eq!(Aggregate::Mean.reduce(&points, 3), points.to_vec());
+    assert_eq!(Aggregate::Mean.reduce(&points, 10), points.to_vec());
+    assert_eq!(Aggregate::Mean.reduce(&points, 0), points.to_vec());
+}
+
+#[test]
+fn uneven_buckets_cover_every_point() {
+    let points = [1.0, 2.0, 3.0, 4.0, 5.0];
+
+    assert_eq!(Aggregate::Mean.reduce(&points, 2), vec![1.5, 4.0]);
+    assert_eq!(Aggregate::Min.reduce(&points, 2), vec![1.0, 3.0]);
+    assert_eq!(Aggregate::Max.reduce(&points, 2), vec![2.0, 5.0]);
+    assert_eq!(Aggregate::Last.reduce(&points, 2), vec![2.0, 5.0]);
+}
+
+#[test]
+fn lttb_keeps_the_first_and_last_points() {
+    let points: Vec<f32> = (0..10).map(|point| point as f32).collect();
+    let sampled = Aggregate::Lttb.reduce(&points, 4);
+
+    assert_eq!(sampled.len(), 4);
+    assert_eq!(sampled.first(), Some(&0.0));
+    assert_eq!(sampled.last(), Some(&9.0));
+}
+
+#[test]
+fn lttb_keeps_a_spike() {
+    let mut points = vec![0.0; 10];
+    points[5] = 10.0;
+
+    assert!(Aggregate::Lttb.reduce(&points, 5).contains(&10.0));
+}
+
+#[test]
+fn lttb_with_fewer_than_three_buckets_keeps_the_last_point_of_each() {
+    let points: Vec<f32> = (0..10).map(|point| point as f32).collect();
+
+    assert_eq!(Aggregate::Lttb.reduce(&points, 2), vec![4.0, 9.0]);
+    assert_eq!(Aggregate::Lttb.reduce(&points, 1), vec![9.0]);
+}
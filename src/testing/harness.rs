@@ -0,0 +1,168 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use anathema::geometry::Size;
+use anathema::resolver::ValueKind;
+use crate::attributes::attributes::Attributes;
+use crate::effects::fire::Fire;
+use crate::effects::matrix::Matrix;
+use crate::graph::graph::{Graph, GraphData, GraphSeries};
+use crate::resources::cpus::CpusMessage;
+use crate::starfield::starfield::{Starfield, StarfieldState};
+use crate::surface::text_grid::TextGrid;
+
+/// Drives widgets without a terminal.
+/// Every tick uses the same dt, and whatever the widget draws ends up in a
+/// `TextGrid` of the given size.
+pub struct Harness {
+    size: Size,
+    dt: Duration,
+}
+
+impl Harness {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            size: Size::new(width, height),
+            // The runtime ticks at 10 fps
+            dt: Duration::from_millis(100),
+        }
+    }
+
+    /// Ticks the graph once for every frame of data, reading the attributes the
+    /// same way `on_tick` does, and draws the result
+    pub fn render_graph(&self, graph: &mut Graph, attributes: &TestAttributes, frames: &[GraphData]) -> String {
+        for graph_data in frames {
+            graph.update(attributes, Some(&data_value(graph_data)), self.dt);
+            graph.prepare(self.size);
+        }
+
+        let mut grid = TextGrid::new(self.size);
+        graph.render(&mut grid, self.size);
+        grid.to_string()
+    }
+
+    /// Draws the per core usage of every message as the bar graph in
+    /// `templates/resources/cpus.aml` does, one frame per message
+    pub fn render_cpus(&self, messages: &[CpusMessage]) -> String {
+        let frames: Vec<GraphData> = messages.iter()
+            .map(|message| series(&[&message.cpu_usage]))
+            .collect();
+
+        self.render_graph(&mut Graph::default(), &attributes(&[("type", "bar")]), &frames)
+    }
+
+    /// Ticks the starfield on a canvas of the harness size and draws the final frame
+    pub fn render_starfield(&self, starfield: &mut Starfield, attributes: &TestAttributes, ticks: usize) -> String {
+        let (width, height) = (self.size.width as i32, self.size.height as i32);
+        let mut state = StarfieldState::default();
+        let mut grid = TextGrid::new(self.size);
+
+        for _ in 0..ticks {
+            let reseeded = starfield.configure(attributes);
+            starfield.fit(width, height, reseeded);
+            starfield.update_stars(&mut state, &mut grid, self.dt, width, height);
+        }
+        grid.to_string()
+    }
+
+    /// Ticks the fire on a canvas of the harness size and draws the final frame
+    pub fn render_fire(&self, fire: &mut Fire, attributes: &TestAttributes, ticks: usize) -> String {
+        let mut grid = TextGrid::new(self.size);
+        for _ in 0..ticks {
+            fire.configure(attributes);
+            fire.tick(&mut grid, self.size, self.dt);
+        }
+        grid.to_string()
+    }
+
+    /// Ticks the rain on a canvas of the harness size and draws the final frame
+    pub fn render_matrix(&self, matrix: &mut Matrix, attributes: &TestAttributes, ticks: usize) -> String {
+        let mut grid = TextGrid::new(self.size);
+        for _ in 0..ticks {
+            matrix.configure(attributes);
+            matrix.tick(&mut grid, self.size, self.dt);
+        }
+        grid.to_string()
+    }
+}
+
+/// Attributes as they would be written in a template, all values as text
+#[derive(Default)]
+pub struct TestAttributes {
+    values: Vec<(String, String)>,
+}
+
+impl Attributes for TestAttributes {
+    fn text(&self, key: &str) -> Option<&str> {
+        self.values.iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn number(&self, key: &str) -> Option<f32> {
+        self.text(key)?.parse().ok()
+    }
+
+    fn integer(&self, key: &str) -> Option<i64> {
+        self.text(key)?.parse().ok()
+    }
+
+    fn flag(&self, key: &str) -> Option<bool> {
+        self.text(key)?.parse().ok()
+    }
+
+    /// A list is written as comma separated values
+    fn numbers(&self, key: &str) -> Option<Vec<f32>> {
+        Some(self.text(key)?.split(',').filter_map(|value| value.trim().parse().ok()).collect())
+    }
+
+    fn texts(&self, key: &str) -> Option<Vec<String>> {
+        Some(self.text(key)?.split(',').map(|value| value.trim().to_string()).collect())
+    }
+}
+
+pub fn attributes(values: &[(&str, &str)]) -> TestAttributes {
+    TestAttributes {
+        values: values.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+    }
+}
+
+/// The `data` attribute a template would pass for the graph data
+fn data_value(graph_data: &GraphData) -> ValueKind {
+    let float = |value: f32| ValueKind::Float(value as f64);
+
+    ValueKind::List(graph_data.series.iter()
+        .map(|series| {
+            let points = series.points.iter().map(|&point| float(point));
+            let pairs = series.pairs.iter()
+                .map(|&(x, y)| ValueKind::List([float(x), float(y)].into_iter().collect()));
+            let candles = series.candles.iter()
+                .map(|candle| ValueKind::List([candle.open, candle.high, candle.low, candle.close].into_iter().map(float).collect()));
+            ValueKind::List(points.chain(pairs).chain(candles).collect())
+        })
+        .collect())
+}
+
+pub fn series(series: &[&[f32]]) -> GraphData {
+    GraphData {
+        series: series.iter()
+            .map(|points| GraphSeries { points: points.to_vec(), ..Default::default() })
+            .collect(),
+    }
+}
+
+/// Compares the output with `tests/golden/<name>.txt`.
+/// Run the tests with `UPDATE_GOLDEN=1` to write the current output instead.
+pub fn assert_golden(name: &str, actual: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", &format!("{name}.txt")].iter().collect();
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing golden file {}, run with UPDATE_GOLDEN=1 to create it", path.display()));
+    assert_eq!(expected, actual, "output does not match {}", path.display());
+}
@@ -0,0 +1,20 @@
+use crate::heatmap::heatmap::Heatmap;
+
+#[test]
+fn every_row_shows_up_when_there_are_more_rows_than_half_rows() {
+    // 64 cores on a heatmap two lines tall
+    let mut heatmap = Heatmap { rows: vec![vec![0.0; 4]; 64] };
+    heatmap.rows[17][0] = 50.0;
+    heatmap.rows[63][0] = 100.0;
+
+    let column: Vec<Option<f32>> = (0..4).map(|half_row| heatmap.cell(half_row, 0, 4, 4, 4)).collect();
+    assert_eq!(column, vec![Some(0.0), Some(50.0), Some(0.0), Some(100.0)]);
+}
+
+#[test]
+fn rows_are_stretched_when_there_are_fewer_rows_than_half_rows() {
+    let heatmap = Heatmap { rows: vec![vec![1.0], vec![2.0]] };
+
+    let column: Vec<Option<f32>> = (0..4).map(|half_row| heatmap.cell(half_row, 0, 4, 1, 1)).collect();
+    assert_eq!(column, vec![Some(1.0), Some(1.0), Some(2.0), Some(2.0)]);
+}
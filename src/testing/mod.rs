@@ -0,0 +1,8 @@
+mod attributes;
+mod binning;
+mod downsample;
+pub mod harness;
+mod heatmap;
+mod scale;
+mod snapshots;
+mod timing;
@@ -0,0 +1,56 @@
+use crate::graph::scale::Scale;
+
+#[test]
+fn log_scale_cannot_show_zero_or_negative_values() {
+    assert_eq!(Scale::Log.transform(0.0), None);
+    assert_eq!(Scale::Log.transform(-5.0), None);
+    assert_eq!(Scale::Log.transform(100.0), Some(2.0));
+    assert_eq!(Scale::Log.project(0.0, (1.0, 100.0), 10), None);
+}
+
+#[test]
+fn log_domain_ignores_values_that_are_not_positive() {
+    assert_eq!(Scale::Log.domain([0.0, -3.0, 20.0].into_iter()), (10.0, 100.0));
+}
+
+#[test]
+fn log_domain_falls_back_when_nothing_is_positive() {
+    assert_eq!(Scale::Log.domain([0.0, -3.0].into_iter()), (1.0, 10.0));
+    assert_eq!(Scale::Log.domain(std::iter::empty()), (1.0, 10.0));
+}
+
+#[test]
+fn log_domain_snaps_to_whole_decades() {
+    assert_eq!(Scale::Log.domain([3.0, 250.0].into_iter()), (1.0, 1000.0));
+    // A single decade still has some height
+    assert_eq!(Scale::Log.domain([10.0].into_iter()), (10.0, 100.0));
+}
+
+#[test]
+fn linear_domain_includes_zero() {
+    assert_eq!(Scale::Linear.domain([2.0, 5.0].into_iter()), (0.0, 5.0));
+    assert_eq!(Scale::Linear.domain([-3.0, 5.0].into_iter()), (-3.0, 5.0));
+    assert_eq!(Scale::Linear.tight_domain([2.0, 5.0].into_iter()), (2.0, 5.0));
+}
+
+#[test]
+fn linear_ticks_are_on_nice_steps() {
+    assert_eq!(Scale::Linear.ticks((0.0, 10.0), 3), vec![0.0, 5.0, 10.0]);
+    assert_eq!(Scale::Linear.ticks((-10.0, 10.0), 3), vec![-10.0, 0.0, 10.0]);
+}
+
+#[test]
+fn log_ticks_are_whole_decades() {
+    assert_eq!(Scale::Log.ticks((1.0, 1000.0), 4), vec![1.0, 10.0, 100.0, 1000.0]);
+    assert_eq!(Scale::Log.ticks((1.0, 1000.0), 2), vec![1.0, 100.0]);
+}
+
+#[test]
+fn sqrt_ticks_are_evenly_spaced_on_the_canvas() {
+    assert_eq!(Scale::Sqrt.ticks((0.0, 100.0), 6), vec![0.0, 4.0, 16.0, 36.0, 64.0, 100.0]);
+
+    // Every tick gets a row of its own instead of overwriting the one above
+    let labels = Scale::Sqrt.labels((0.0, 100.0), 18);
+    let shown = labels.iter().filter(|label| !label.trim().is_empty()).count();
+    assert_eq!(shown, Scale::Sqrt.ticks((0.0, 100.0), 6).len());
+}
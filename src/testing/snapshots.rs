@@ -0,0 +1,277 @@
+use anathema::geometry::{Pos, Size};
+use crate::effects::fire::Fire;
+use crate::effects::matrix::{Matrix, GLYPHS};
+use crate::graph::export::to_csv;
+use crate::graph::graph::{canvas_cell, Candle, Graph, GraphData, GraphSeries};
+use crate::graph::graph_wrapper::generate_random_series;
+use crate::resources::cpus::CpusMessage;
+use crate::random::seed::SeededRng;
+use crate::starfield::starfield::Starfield;
+use crate::testing::harness::{assert_golden, attributes, series, Harness};
+
+fn cpus_message(cpu_usage: Vec<f32>) -> CpusMessage {
+    CpusMessage {
+        cpu_usage,
+        total_usage: 0.0,
+        memory: (0, 0),
+        memory_cache: 0,
+        memory_free: 0,
+        disk: (0, 0),
+        disk_mounts: vec![],
+    }
+}
+
+#[test]
+fn bar_graph() {
+    let output = Harness::new(8, 4).render_graph(&mut Graph::default(), &attributes(&[("type", "bar")]), &[series(&[&[1.0, 2.0, 3.0, 4.0]])]);
+    assert_golden("graph_bar", &output);
+}
+
+#[test]
+fn dense_bar_graph_keeps_the_newest_bucket_on_the_canvas() {
+    // Only the last point stands out, so it has to end up in the right-most bar
+    let mut points = vec![1.0; 19];
+    points.push(4.0);
+    let attributes = attributes(&[("type", "bar"), ("aggregate", "max")]);
+    let output = Harness::new(8, 4).render_graph(&mut Graph::default(), &attributes, &[series(&[&points])]);
+
+    assert_eq!(output, "       █\n       █\n       █\n █ █ █ █\n");
+}
+
+#[test]
+fn dense_candles_are_merged_to_fit() {
+    let mut graph = Graph::default();
+    let candles = (0..20)
+        .map(|index| Candle { open: index as f32, high: index as f32 + 2.0, low: 0.0, close: index as f32 + 1.0 })
+        .collect();
+    let data = GraphData { series: vec![GraphSeries { candles, ..Default::default() }] };
+
+    Harness::new(8, 4).render_graph(&mut graph, &attributes(&[("type", "candlestick")]), &[data]);
+    let candles = &graph.graph_data.as_ref().unwrap().series[0].candles;
+
+    assert_eq!(candles.len(), 4);
+    assert_eq!(candles[3].close, 20.0);
+    assert_eq!(candles[3].high, 21.0);
+}
+
+#[test]
+fn point_graph() {
+    let attributes = attributes(&[("markers", "@")]);
+    let output = Harness::new(8, 4).render_graph(&mut Graph::default(), &attributes, &[series(&[&[0.0, 2.0, 4.0]])]);
+    assert_golden("graph_point", &output);
+}
+
+#[test]
+fn streaming_graph_fills_from_the_right() {
+    let attributes = attributes(&[("mode", "stream"), ("point_width", "1")]);
+    let frames = [series(&[&[1.0]]), series(&[&[2.0]]), series(&[&[3.0]])];
+    let output = Harness::new(8, 4).render_graph(&mut Graph::default(), &attributes, &frames);
+    assert_golden("graph_stream", &output);
+}
+
+#[test]
+fn streaming_graph_keeps_scrolling_steady_values() {
+    let attributes = attributes(&[("mode", "stream"), ("point_width", "1")]);
+    let frames = vec![series(&[&[5.0]]); 3];
+    let output = Harness::new(8, 4).render_graph(&mut Graph::default(), &attributes, &frames);
+    assert_eq!(output.matches('@').count(), 3);
+}
+
+#[test]
+fn cpus() {
+    let messages = vec![
+        cpus_message(vec![10.0, 10.0, 10.0, 10.0]),
+        cpus_message(vec![20.0, 40.0, 100.0, 60.0]),
+    ];
+
+    let output = Harness::new(8, 5).render_cpus(&messages);
+    assert_golden("cpus", &output);
+}
+
+#[test]
+fn starfield_stays_on_the_canvas() {
+    let output = Harness::new(40, 20).render_starfield(&mut Starfield::default(), &attributes(&[("stars", "20")]), 50);
+
+    assert_eq!(output.lines().count(), 20);
+    assert!(output.lines().all(|line| line.chars().count() <= 40));
+    assert!(output.chars().all(|character| matches!(character, ' ' | '.' | '·' | '*' | '✦' | '\n')));
+}
+
+#[test]
+fn seeded_starfield_is_reproducible() {
+    let harness = Harness::new(40, 20);
+    let attributes = attributes(&[("seed", "7"), ("stars", "20")]);
+    let first = harness.render_starfield(&mut Starfield::default(), &attributes, 50);
+    let second = harness.render_starfield(&mut Starfield::default(), &attributes, 50);
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn starfield() {
+    let attributes = attributes(&[("seed", "7"), ("stars", "30")]);
+    let output = Harness::new(40, 20).render_starfield(&mut Starfield::default(), &attributes, 20);
+    assert_golden("starfield", &output);
+}
+
+#[test]
+fn seeded_graph_data_is_reproducible() {
+    let first = generate_random_series(SeededRng::from_seed(7).rng(), 2, 10);
+    let second = generate_random_series(SeededRng::from_seed(7).rng(), 2, 10);
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn stopped_starfield_stays_still() {
+    let harness = Harness::new(40, 20);
+    let attributes = attributes(&[("seed", "7"), ("stars", "20"), ("speed", "0")]);
+    let first = harness.render_starfield(&mut Starfield::default(), &attributes, 1);
+    let later = harness.render_starfield(&mut Starfield::default(), &attributes, 50);
+
+    assert_eq!(first, later);
+}
+
+#[test]
+fn starfield_trails_stay_on_the_canvas() {
+    let attributes = attributes(&[("seed", "7"), ("stars", "20"), ("speed", "3"), ("trails", "0.5")]);
+    let output = Harness::new(40, 20).render_starfield(&mut Starfield::default(), &attributes, 50);
+
+    assert_eq!(output.lines().count(), 20);
+    assert!(output.lines().all(|line| line.chars().count() <= 40));
+}
+
+#[test]
+fn starfield_shrinks_with_the_canvas() {
+    let mut starfield = Starfield::default();
+    let attributes = attributes(&[("seed", "7"), ("stars", "20")]);
+    Harness::new(40, 20).render_starfield(&mut starfield, &attributes, 10);
+    let output = Harness::new(20, 10).render_starfield(&mut starfield, &attributes, 10);
+
+    assert_eq!(output.lines().count(), 10);
+    assert!(output.lines().all(|line| line.chars().count() <= 20));
+}
+
+#[test]
+fn parallax_starfield_scrolls_within_the_canvas() {
+    let attributes = attributes(&[("seed", "7"), ("stars", "20"), ("mode", "parallax")]);
+    let output = Harness::new(40, 20).render_starfield(&mut Starfield::default(), &attributes, 50);
+
+    assert_eq!(output.lines().count(), 20);
+    assert!(output.lines().all(|line| line.chars().count() <= 40));
+    // Stars on the same row can land in the same cell
+    let drawn = output.chars().filter(|character| !character.is_whitespace()).count();
+    assert!(drawn > 0 && drawn <= 20);
+}
+
+#[test]
+fn bars_ease_towards_new_data() {
+    let harness = Harness::new(8, 8);
+    let rising = series(&[&[1.0, 8.0]]);
+    let falling = series(&[&[8.0, 1.0]]);
+
+    let eased = attributes(&[("type", "bar"), ("transition", "500")]);
+    let mut graph = Graph::default();
+    let halfway = harness.render_graph(&mut graph, &eased, &[rising.clone(), falling.clone(), falling.clone()]);
+    let settled = harness.render_graph(&mut graph, &eased, &vec![falling.clone(); 5]);
+
+    let expected = harness.render_graph(&mut Graph::default(), &attributes(&[("type", "bar")]), &[rising, falling]);
+
+    assert_ne!(halfway, expected);
+    assert_eq!(settled, expected);
+}
+
+#[test]
+fn csv_has_every_point_that_was_received() {
+    let mut graph = Graph::default();
+    let attributes = attributes(&[("type", "bar"), ("transition", "500")]);
+
+    let points: Vec<f32> = (0..20).map(|index| index as f32).collect();
+    let doubled: Vec<f32> = points.iter().map(|point| point * 2.0).collect();
+    Harness::new(8, 4).render_graph(&mut graph, &attributes, &[series(&[&points]), series(&[&doubled])]);
+    let csv = to_csv(&graph);
+
+    // Neither downsampled to fit the canvas nor eased part way
+    assert_eq!(csv.lines().count(), 21);
+    assert_eq!(csv.lines().last(), Some("0,19,19,38,,,,"));
+}
+
+#[test]
+fn csv_of_a_streaming_graph_has_the_whole_history() {
+    let mut graph = Graph::default();
+    let frames: Vec<_> = (0..20).map(|index| series(&[&[index as f32]])).collect();
+    Harness::new(8, 4).render_graph(&mut graph, &attributes(&[("mode", "stream")]), &frames);
+
+    assert_eq!(to_csv(&graph).lines().count(), 21);
+}
+
+#[test]
+fn fire_stays_on_the_canvas() {
+    let harness = Harness::new(20, 6);
+    let attributes = attributes(&[("seed", "7")]);
+    let output = harness.render_fire(&mut Fire::default(), &attributes, 20);
+
+    assert_eq!(output.lines().count(), 6);
+    // Every cell is a pair of pixels
+    assert!(output.lines().all(|line| line.chars().count() == 20 && line.chars().all(|character| character == '▀')));
+    assert_eq!(output, harness.render_fire(&mut Fire::default(), &attributes, 20));
+}
+
+#[test]
+fn matrix_stays_on_the_canvas() {
+    let harness = Harness::new(20, 6);
+    let attributes = attributes(&[("seed", "7")]);
+    let output = harness.render_matrix(&mut Matrix::default(), &attributes, 5);
+
+    assert_eq!(output.lines().count(), 6);
+    assert!(output.lines().all(|line| line.chars().count() <= 20));
+    assert!(output.chars().all(|character| matches!(character, ' ' | '\n') || GLYPHS.contains(character)));
+    assert_eq!(output, harness.render_matrix(&mut Matrix::default(), &attributes, 5));
+}
+
+#[test]
+fn edges_are_labelled_under_the_bars() {
+    let mut graph = Graph::default();
+    let attributes = attributes(&[("type", "bar"), ("x_edges", "0,1,2,3,4")]);
+    Harness::new(16, 4).render_graph(&mut graph, &attributes, &[series(&[&[1.0, 2.0, 3.0, 4.0]])]);
+
+    // The bars are three columns wide and start at columns 1, 5, 9 and 13
+    assert_eq!(graph.x_labels(Size::new(16, 4)), " 0   1   2   3 4");
+}
+
+#[test]
+fn candles_fill_the_height_of_the_canvas() {
+    let mut graph = Graph::default();
+    let candles = vec![
+        Candle { open: 100.0, high: 103.0, low: 100.0, close: 102.0 },
+        Candle { open: 102.0, high: 105.0, low: 101.0, close: 104.0 },
+    ];
+    let data = GraphData { series: vec![GraphSeries { candles, ..Default::default() }] };
+    Harness::new(8, 4).render_graph(&mut graph, &attributes(&[("type", "candlestick")]), &[data]);
+
+    assert_eq!(graph.range, (100.0, 105.0));
+}
+
+#[test]
+fn only_clicks_on_the_canvas_move_the_cursor() {
+    let (canvas, size) = (Pos::new(2, 3), Size::new(8, 4));
+
+    assert_eq!(canvas_cell(Pos::new(5, 4), canvas, size), Some((3, 1)));
+    // Same column, but below the canvas
+    assert_eq!(canvas_cell(Pos::new(5, 7), canvas, size), None);
+    assert_eq!(canvas_cell(Pos::new(5, 2), canvas, size), None);
+    assert_eq!(canvas_cell(Pos::new(10, 4), canvas, size), None);
+}
+
+#[test]
+fn csv_of_a_box_plot_is_in_the_order_it_was_read() {
+    let mut graph = Graph::default();
+    // The harness passes candles as `[open, high, low, close]` lists, a box plot reads them as `[min, p50, p99, max]`
+    let candles = vec![Candle { open: 1.0, high: 5.0, low: 9.0, close: 10.0 }];
+    let data = GraphData { series: vec![GraphSeries { candles, ..Default::default() }] };
+    Harness::new(8, 4).render_graph(&mut graph, &attributes(&[("type", "box")]), &[data]);
+    let csv = to_csv(&graph);
+
+    assert_eq!(csv.lines().next(), Some("series,index,x,y,min,p50,p99,max"));
+    assert_eq!(csv.lines().nth(1), Some("0,0,0,,1,5,9,10"));
+}
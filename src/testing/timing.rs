@@ -0,0 +1,86 @@
+use std::time::Duration;
+use crate::timing::easing::Easing;
+use crate::timing::interval::Interval;
+use crate::timing::step::FixedStep;
+use crate::timing::tween::Tween;
+
+const TICK: Duration = Duration::from_millis(100);
+
+#[test]
+fn fixed_step_runs_at_its_own_rate() {
+    let mut step = FixedStep::new(20.0);
+    let steps: u32 = (0..10).map(|_| step.advance(TICK)).sum();
+
+    assert_eq!(steps, 20);
+}
+
+#[test]
+fn fixed_step_does_not_catch_up_on_long_pauses() {
+    let mut step = FixedStep::new(20.0);
+
+    assert_eq!(step.advance(Duration::from_secs(60)), 10);
+    assert_eq!(step.advance(Duration::ZERO), 0);
+}
+
+#[test]
+fn interval_is_ready_straight_away_and_then_once_per_period() {
+    let mut interval = Interval::new(Duration::from_secs(1));
+    let ready: Vec<bool> = (0..21).map(|_| interval.ready(TICK)).collect();
+
+    let ticks: Vec<usize> = ready.iter().enumerate()
+        .filter(|(_, ready)| **ready)
+        .map(|(tick, _)| tick)
+        .collect();
+    assert_eq!(ticks, vec![0, 10, 20]);
+}
+
+#[test]
+fn interval_keeps_its_rate_when_ticks_are_uneven() {
+    let mut interval = Interval::new(Duration::from_millis(100));
+    let ready = (0..20)
+        .map(|tick| Duration::from_millis(if tick % 2 == 0 { 99 } else { 101 }))
+        .filter(|&dt| interval.ready(dt))
+        .count();
+
+    assert_eq!(ready, 20);
+}
+
+#[test]
+fn interval_does_not_catch_up_on_long_pauses() {
+    let mut interval = Interval::new(Duration::from_millis(100));
+    interval.ready(TICK);
+
+    assert!(interval.ready(Duration::from_secs(60)));
+    assert!(!interval.ready(Duration::ZERO));
+}
+
+#[test]
+fn easing_starts_and_ends_in_place() {
+    for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+        assert_eq!(easing.apply(0.0), 0.0);
+        assert_eq!(easing.apply(1.0), 1.0);
+    }
+}
+
+#[test]
+fn tween_reaches_its_target() {
+    let mut tween = Tween::at(0.0, Duration::from_millis(500), Easing::Linear);
+    tween.retarget(10.0);
+
+    tween.advance(Duration::from_millis(250));
+    assert_eq!(tween.value(), 5.0);
+
+    tween.advance(Duration::from_millis(250));
+    assert!(tween.is_done());
+    assert_eq!(tween.value(), 10.0);
+}
+
+#[test]
+fn retargeting_starts_from_the_current_value() {
+    let mut tween = Tween::at(0.0, Duration::from_millis(500), Easing::Linear);
+    tween.retarget(10.0);
+    tween.advance(Duration::from_millis(250));
+
+    tween.retarget(0.0);
+    assert_eq!(tween.value(), 5.0);
+}
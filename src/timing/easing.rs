@@ -0,0 +1,34 @@
+/// How a tween moves from its start to its end
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl From<&str> for Easing {
+    fn from(value: &str) -> Self {
+        match value {
+            "linear" => Easing::Linear,
+            "ease_in" => Easing::EaseIn,
+            "ease_in_out" => Easing::EaseInOut,
+            _ => Easing::EaseOut,
+        }
+    }
+}
+
+impl Easing {
+    /// Maps the progress `t`, clamped to `0.0..=1.0`, onto the curve
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
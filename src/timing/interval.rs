@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+/// Throttles work to once per period, however often the runtime ticks.
+/// The first tick is always ready so there is something to show straight away.
+pub struct Interval {
+    period: Duration,
+    /// Time since the last period was up, `None` until the first tick
+    elapsed: Option<Duration>,
+}
+
+impl Interval {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            elapsed: None,
+        }
+    }
+
+    pub fn set_period(&mut self, period: Duration) {
+        self.period = period;
+    }
+
+    /// Adds the time that has passed and returns true once a period is up.
+    /// Time past the end of the period counts towards the next one, so ticks
+    /// that do not line up with the period do not slow it down.
+    /// Periods that were missed are not made up for.
+    pub fn ready(&mut self, dt: Duration) -> bool {
+        let Some(elapsed) = self.elapsed.as_mut() else {
+            self.elapsed = Some(Duration::ZERO);
+            return true;
+        };
+
+        *elapsed += dt;
+        if *elapsed < self.period {
+            return false;
+        }
+
+        *elapsed -= self.period;
+        if *elapsed >= self.period {
+            *elapsed = Duration::ZERO;
+        }
+        true
+    }
+}
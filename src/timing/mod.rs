@@ -0,0 +1,4 @@
+pub mod easing;
+pub mod interval;
+pub mod step;
+pub mod tween;
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+/// Never catch up on more steps than this in a single tick, so a long pause
+/// does not stall the next frame
+const MAX_STEPS: u32 = 10;
+
+/// Runs a simulation at a fixed rate, independent of how often the runtime ticks.
+/// The time that has passed is accumulated and used up a step at a time.
+pub struct FixedStep {
+    step: Duration,
+    accumulated: Duration,
+}
+
+impl FixedStep {
+    /// `rate` is the number of steps per second
+    pub fn new(rate: f32) -> Self {
+        Self {
+            step: Duration::from_secs_f64(1.0 / rate.max(0.1) as f64),
+            accumulated: Duration::ZERO,
+        }
+    }
+
+    pub fn set_rate(&mut self, rate: f32) {
+        self.step = Duration::from_secs_f64(1.0 / rate.max(0.1) as f64);
+    }
+
+    /// Adds the time that has passed and returns how many steps to run
+    pub fn advance(&mut self, dt: Duration) -> u32 {
+        self.accumulated += dt;
+
+        let mut steps = 0;
+        while self.accumulated >= self.step {
+            self.accumulated -= self.step;
+            steps += 1;
+        }
+
+        if steps > MAX_STEPS {
+            self.accumulated = Duration::ZERO;
+            steps = MAX_STEPS;
+        }
+        steps
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+use crate::timing::easing::Easing;
+
+/// Moves a value from one number to another over a duration
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tween {
+    from: f32,
+    to: f32,
+    elapsed: Duration,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Tween {
+    /// A tween that is already at `value`
+    pub fn at(value: f32, duration: Duration, easing: Easing) -> Self {
+        Self {
+            from: value,
+            to: value,
+            elapsed: duration,
+            duration,
+            easing,
+        }
+    }
+
+    /// Heads for a new value, starting from wherever the tween is now so a
+    /// tween that is interrupted does not jump
+    pub fn retarget(&mut self, to: f32) {
+        if to == self.to {
+            return;
+        }
+
+        self.from = self.value();
+        self.to = to;
+        self.elapsed = Duration::ZERO;
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+
+    pub fn advance(&mut self, dt: Duration) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+    }
+
+    pub fn value(&self) -> f32 {
+        if self.is_done() {
+            return self.to;
+        }
+
+        let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        self.from + (self.to - self.from) * self.easing.apply(t)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}